@@ -0,0 +1,134 @@
+//! Check replaced output for leaks: any fragment of an original hash that's
+//! still there, eg a short prefix of a token that was also quoted in prose.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// The fragments of the original hashes replaced so far, along with their
+/// replacements.
+#[derive(Debug)]
+pub struct Audit {
+    /// Length of the fragments looked for
+    len: usize,
+    /// Every fragment of that length, lowercased
+    fragments: HashSet<String>,
+    /// The replacements (lowercased, without whitespace), by their first `len`
+    /// characters
+    replacements: HashMap<String, HashSet<Vec<char>>>,
+}
+
+impl Audit {
+    /// Look for fragments of at least `len` characters.
+    pub fn new(len: usize) -> Self {
+        Audit {
+            len: len.max(1),
+            fragments: HashSet::new(),
+            replacements: HashMap::new(),
+        }
+    }
+
+    /// Note a replacement. Only the part that was actually replaced counts, so
+    /// eg the public prefix kept on a `ghp_` token isn't a leak.
+    pub fn add(&mut self, original: &str, replaced: &str) {
+        let original: Vec<char> = original.chars().collect();
+        let replaced: Vec<char> = replaced.chars().collect();
+        let prefix = common_len(original.iter(), replaced.iter());
+        let suffix = common_len(original[prefix..].iter().rev(), replaced.iter().rev());
+        let secret = lowercase(&original[prefix..original.len() - suffix]);
+        for fragment in secret.windows(self.len) {
+            self.fragments.insert(fragment.iter().collect());
+        }
+        let replaced: Vec<char> = replaced
+            .into_iter()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        if replaced.len() >= self.len {
+            let replaced = lowercase(&replaced);
+            self.replacements
+                .entry(replaced[..self.len].iter().collect())
+                .or_default()
+                .insert(replaced);
+        }
+    }
+
+    /// All the distinct fragments found in some output. Case is ignored, as is
+    /// whitespace, so a hash split across lines is still found. Windows lying
+    /// entirely within replacements (including adjacent ones) are skipped, as
+    /// any fragment there is just a coincidence of the random replacements.
+    pub fn check(&self, output: &str) -> BTreeSet<String> {
+        let chars: Vec<char> = output.chars().filter(|c| !c.is_whitespace()).collect();
+        let chars = lowercase(&chars);
+        let windows: Vec<String> = chars
+            .windows(self.len)
+            .map(|window| window.iter().collect())
+            .collect();
+        let mut replaced = vec![false; chars.len()];
+        for (i, window) in windows.iter().enumerate() {
+            for replacement in self.replacements.get(window).into_iter().flatten() {
+                if chars[i..].starts_with(replacement) {
+                    replaced[i..i + replacement.len()].fill(true);
+                }
+            }
+        }
+        windows
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !replaced[*i..*i + self.len].iter().all(|r| *r))
+            .map(|(_, fragment)| fragment)
+            .filter(|fragment| self.fragments.contains(fragment))
+            .collect()
+    }
+}
+
+/// How many items two sequences start with in common.
+fn common_len<'a>(
+    a: impl Iterator<Item = &'a char>,
+    b: impl Iterator<Item = &'a char>,
+) -> usize {
+    a.zip(b).take_while(|(x, y)| x == y).count()
+}
+
+/// Lowercase characters one-for-one.
+fn lowercase(chars: &[char]) -> Vec<char> {
+    chars.iter().map(|c| c.to_ascii_lowercase()).collect()
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::Audit;
+
+    #[test]
+    fn leaks() {
+        let mut audit = Audit::new(6);
+        audit.add("ghp_9d0c3ffe6783d59b", "ghp_1a5b8bwz7549n47c");
+        audit.add("short", "other");
+        assert!(audit.check("ghp_1a5b8bwz7549n47c is gone").is_empty());
+        assert!(audit.check("no ghp_ prefix leaks, nor shor").is_empty());
+        assert_eq!(
+            audit
+                .check("but 9D0C3F and 6783\n    d59b do")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            ["6783d5", "783d59", "83d59b", "9d0c3f"]
+        );
+    }
+
+    #[test]
+    fn replacements() {
+        // Fragments of originals within replacements (or spanning adjacent
+        // ones) are coincidences, but not when they spill over.
+        let mut audit = Audit::new(6);
+        audit.add("9d0c3ffe6783d59b", "1a5b8bwz7549n47c");
+        audit.add("2f8e4a7c1b6d3e9f", "6783d5k2m9x4q1b9");
+        audit.add("5c1e8f2a7d4b9e3c", "z7x2q1p9d0c3fr4w");
+        assert!(audit.check("1a5b8bwz7549n47c 6783d5k2m9x4q1b9").is_empty());
+        assert!(audit.check("z7x2q1p9d0c3fr4w").is_empty());
+        assert_eq!(
+            audit
+                .check("1a5b8bwz7549n47c9d0c3f")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            ["9d0c3f"]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Minimal base64 decoding/encoding, remembering the "shape" of the original
+//! (standard vs URL-safe alphabet, padded or not) so that modified contents can
+//! be re-encoded to look just like the original.
+
+/// The variant of base64 used by some string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shape {
+    /// Uses `-` and `_` rather than `+` and `/`
+    pub url_safe: bool,
+    /// Has trailing `=` padding
+    pub padded: bool,
+}
+
+const STANDARD: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Decode a base64 string, in either alphabet, padded or not. Returns `None` if
+/// this isn't valid base64 (including if it mixes the two alphabets).
+pub fn decode(s: &str) -> Option<(Vec<u8>, Shape)> {
+    let data = s.trim_end_matches('=');
+    let padding = s.len() - data.len();
+    let url_safe = data.contains(['-', '_']);
+    if (url_safe && data.contains(['+', '/'])) || padding > 2 || data.len() % 4 == 1 {
+        return None;
+    }
+    if padding > 0 && !(data.len() + padding).is_multiple_of(4) {
+        return None;
+    }
+    let alphabet = if url_safe { URL_SAFE } else { STANDARD };
+
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in data.bytes() {
+        let value = alphabet.iter().position(|&a| a == c)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    // Any leftover bits should be zero in canonical base64.
+    if buffer & ((1 << bits) - 1) != 0 {
+        return None;
+    }
+    let shape = Shape {
+        url_safe,
+        padded: padding > 0,
+    };
+    Some((bytes, shape))
+}
+
+/// Encode bytes as base64 of the given shape.
+pub fn encode(bytes: &[u8], shape: Shape) -> String {
+    let alphabet = if shape.url_safe { URL_SAFE } else { STANDARD };
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let buffer = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |buffer, (i, &b)| buffer | (b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            output.push(alphabet[(buffer >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+        if shape.padded {
+            for _ in chunk.len()..3 {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::{Shape, decode, encode};
+
+    #[test]
+    fn round_trip() {
+        for s in [
+            "aGVsbG8=", "aGVsbG8", "aGk-Pz8_", "aGk+Pz8/", "YWJj", "YQ==", "",
+        ] {
+            let (bytes, shape) = decode(s).unwrap_or_else(|| panic!("decoding {}", s));
+            assert_eq!(encode(&bytes, shape), s);
+        }
+        let (bytes, shape) = decode("aGVsbG8=").unwrap();
+        assert_eq!(bytes, b"hello");
+        assert_eq!(
+            shape,
+            Shape {
+                url_safe: false,
+                padded: true
+            }
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for s in ["a", "aGk-Pz8/", "aGVsbG8==", "a===", "aGV*bG8=", "aGVsbG9="] {
+            assert!(decode(s).is_none(), "decoding {}", s);
+        }
+    }
+}
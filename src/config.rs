@@ -0,0 +1,323 @@
+//! Optional configuration file (`.hashmash.toml`), for things that don't fit on
+//! the command line, such as user-defined detection rules:
+//!
+//! ```toml
+//! [[rule]]
+//! kind = "tenant-id"
+//! regex = 'tnt-(?P<body>[0-9a-z]{12})'
+//! replace = "random"      # or "placeholder", or "fixed" (along with `value`)
+//! classify = false        # whether matches must also look like a hash
+//!
+//! [allow]
+//! tokens = ["gpl3Plus"]   # never treated as hashes
+//! regexes = ['SKU-[0-9A-Z]+']
+//! paths = ["vendor/**"]   # files left untouched entirely
+//! ```
+//!
+//! Allowlist files (`--allowlist`) can also be given, with one token per line,
+//! or a regex between slashes (eg `/SKU-[0-9A-Z]+/`). Blank lines, and lines
+//! starting with `#`, are skipped.
+
+use glob::Pattern;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::replace::{Fixed, Placeholder, Randomize, Replacer};
+
+/// Name of the configuration file, looked for in the current directory and its
+/// ancestors.
+const CONFIG_FILENAME: &str = ".hashmash.toml";
+
+/// The contents of a configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// User-defined detection rules
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<Rule>,
+    /// Things to leave alone
+    #[serde(default)]
+    pub allow: Allow,
+}
+
+/// Known-good values, and paths to ignore.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Allow {
+    /// Exact tokens
+    #[serde(default)]
+    tokens: HashSet<String>,
+    /// Regexes, which must match a whole token
+    #[serde(default, deserialize_with = "deserialize_anchored_regexes")]
+    regexes: Vec<Regex>,
+    /// Globs of paths to leave untouched
+    #[serde(default, deserialize_with = "deserialize_globs")]
+    paths: Vec<Pattern>,
+}
+
+/// A user-defined detection rule.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// Label for what this rule detects, eg `tenant-id`
+    pub kind: String,
+    /// What to look for. If there's a `body` capture group, only that part is
+    /// replaced (so eg a prefix can be kept).
+    #[serde(deserialize_with = "deserialize_regex")]
+    pub regex: Regex,
+    /// How to replace matches
+    #[serde(default)]
+    replace: Strategy,
+    /// The replacement for the `fixed` strategy
+    value: Option<String>,
+    /// Whether matches must also be classified as a hash, rather than bypassing
+    /// the classifier
+    #[serde(default)]
+    pub classify: bool,
+}
+
+/// Replacement strategy for a user-defined rule.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Strategy {
+    /// Randomize, preserving character classes
+    #[default]
+    Random,
+    /// Replace with the kind, eg `<tenant-id>`
+    Placeholder,
+    /// Replace with a fixed value
+    Fixed,
+}
+
+impl Config {
+    /// Load the configuration from the given file, or if not given, from the
+    /// nearest `.hashmash.toml` (if any). Then add in any allowlist files.
+    pub fn load(path: Option<&Path>, allowlists: &[PathBuf]) -> io::Result<Self> {
+        let path = match path {
+            Some(path) => Some(path.to_path_buf()),
+            None => find_config()?,
+        };
+        let mut config = match path {
+            Some(path) => Config::load_file(&path)?,
+            None => Config::default(),
+        };
+        for path in allowlists {
+            config.allow.load_file(path)?;
+        }
+        Ok(config)
+    }
+
+    /// Load a configuration file.
+    fn load_file(path: &Path) -> io::Result<Self> {
+        let invalid = |message: String| invalid_data(path, message);
+        let text = fs::read_to_string(path)?;
+        let config: Config =
+            ::toml::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+        for rule in &config.rules {
+            if (rule.replace == Strategy::Fixed) != rule.value.is_some() {
+                return Err(invalid(format!(
+                    "rule `{}`: `value` must be given exactly when `replace = \"fixed\"`",
+                    rule.kind
+                )));
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl Allow {
+    /// Add the entries of an allowlist file.
+    fn load_file(&mut self, path: &Path) -> io::Result<()> {
+        for line in fs::read_to_string(path)?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.strip_prefix('/').and_then(|l| l.strip_suffix('/')) {
+                Some(regex) => {
+                    let regex = anchored_regex(regex)
+                        .map_err(|e| invalid_data(path, e.to_string()))?;
+                    self.regexes.push(regex);
+                }
+                None => {
+                    self.tokens.insert(line.to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a token is known to be fine.
+    pub fn allows(&self, token: &str) -> bool {
+        self.tokens.contains(token) || self.regexes.iter().any(|r| r.is_match(token))
+    }
+
+    /// Whether a file should be left untouched entirely.
+    pub fn ignores_path(&self, path: &str) -> bool {
+        let path = Path::new(path);
+        let path = path.strip_prefix("./").unwrap_or(path);
+        self.paths.iter().any(|pattern| pattern.matches_path(path))
+    }
+}
+
+impl Rule {
+    /// The part of a match that is replaced: the `body` group if there is one,
+    /// otherwise the whole match.
+    pub fn body(&self, token: &str) -> Range<usize> {
+        let body = self.regex.captures(token).and_then(|c| c.name("body"));
+        body.map_or(0..token.len(), |body| body.range())
+    }
+
+    /// Whether matches of this rule are randomized (the default strategy), so
+    /// are replaced however hashes are if that's not by randomizing them.
+    pub fn randomizes(&self) -> bool {
+        self.replace == Strategy::Random
+    }
+
+    /// How to replace (the body of) matches of this rule.
+    pub fn replacer(&self) -> Box<dyn Replacer> {
+        match self.replace {
+            Strategy::Random => Box::new(Randomize),
+            Strategy::Placeholder => Box::new(Placeholder),
+            Strategy::Fixed => Box::new(Fixed(self.value.clone().unwrap_or_default())),
+        }
+    }
+}
+
+/// Look for a config file in the current directory, then its ancestors.
+fn find_config() -> io::Result<Option<PathBuf>> {
+    let cwd = env::current_dir()?;
+    Ok(cwd
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILENAME))
+        .find(|path| path.is_file()))
+}
+
+/// An error for invalid contents of a file.
+fn invalid_data(path: &Path, message: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {}", path.display(), message),
+    )
+}
+
+/// Compile a regex that must match the whole of a string.
+fn anchored_regex(regex: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{})$", regex))
+}
+
+fn deserialize_anchored_regexes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Regex>, D::Error> {
+    let regexes = Vec::<String>::deserialize(deserializer)?;
+    let regexes = regexes.iter().map(|regex| anchored_regex(regex));
+    regexes
+        .collect::<Result<_, _>>()
+        .map_err(serde::de::Error::custom)
+}
+
+fn deserialize_globs<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Pattern>, D::Error> {
+    let globs = Vec::<String>::deserialize(deserializer)?;
+    let globs = globs.iter().map(|glob| Pattern::new(glob));
+    globs
+        .collect::<Result<_, _>>()
+        .map_err(serde::de::Error::custom)
+}
+
+fn deserialize_regex<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Regex, D::Error> {
+    let regex = String::deserialize(deserializer)?;
+    Regex::new(&regex).map_err(serde::de::Error::custom)
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::{Config, Rule};
+    use crate::replace::Context;
+
+    fn parse(text: &str) -> Config {
+        ::toml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn rules() {
+        let config = parse(
+            r#"
+            [[rule]]
+            kind = "tenant-id"
+            regex = 'tnt-(?P<body>[0-9a-z]+)'
+
+            [[rule]]
+            kind = "request-id"
+            regex = 'req_[0-9A-Z]{26}'
+            replace = "placeholder"
+            classify = true
+
+            [[rule]]
+            kind = "customer"
+            regex = 'cust-[0-9]+'
+            replace = "fixed"
+            value = "cust-0"
+            "#,
+        );
+        let [tenant, request, customer] = &config.rules[..] else {
+            panic!("expected three rules");
+        };
+        let context = &mut Context::default();
+        assert_eq!(tenant.body("tnt-3kd9x0a"), 4..11);
+        assert_eq!(request.body("req_01ARZ3NDEKTSV4RRFFQ69G5FAV"), 0..30);
+        let replace = |rule: &Rule, token: &str, context: &mut Context| {
+            rule.replacer().replace(token, &rule.kind, context)
+        };
+        assert_ne!(replace(tenant, "3kd9x0a", context), "3kd9x0a");
+        assert!(!tenant.classify);
+        assert_eq!(
+            replace(request, "req_01ARZ3NDEKTSV4RRFFQ69G5FAV", context),
+            "<request-id>"
+        );
+        assert!(request.classify);
+        assert_eq!(replace(customer, "cust-1234", context), "cust-0");
+    }
+
+    #[test]
+    fn allow() {
+        let config = parse(
+            r#"
+            [allow]
+            tokens = ["gpl3Plus"]
+            regexes = ['SKU-[0-9A-Z]+']
+            paths = ["vendor/**", "*.lock"]
+            "#,
+        );
+        let allow = &config.allow;
+        assert!(allow.allows("gpl3Plus"));
+        assert!(!allow.allows("gpl3Plus2"));
+        assert!(allow.allows("SKU-3X9Q7"));
+        assert!(!allow.allows("xSKU-3X9Q7"));
+        assert!(allow.ignores_path("vendor/a/b.c"));
+        assert!(allow.ignores_path("./vendor/a"));
+        assert!(allow.ignores_path("dir/Cargo.lock"));
+        assert!(!allow.ignores_path("src/vendor.rs"));
+    }
+
+    #[test]
+    fn invalid() {
+        for text in [
+            "[[rule]]\nkind = 'x'\nregex = '('\n",
+            "[[rule]]\nkind = 'x'\nregex = 'x'\nreplace = 'other'\n",
+            "[[rule]]\nkind = 'x'\nregex = 'x'\nunknown = 1\n",
+        ] {
+            assert!(::toml::from_str::<Config>(text).is_err(), "{}", text);
+        }
+    }
+}
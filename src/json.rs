@@ -0,0 +1,320 @@
+//! JSON-aware processing. Rather than running the pre-classifier over raw
+//! lines, we scan the document (or a sequence of documents, as in JSON Lines),
+//! and only consider the decoded contents of string values.
+
+use std::io;
+
+use crate::structured::{Decoded, Fields, PathElem, Quoting, TransformText};
+
+/// Transform a JSON (or JSON Lines) document, applying `transform` to the
+/// selected string values.
+pub fn transform(
+    input: &str,
+    fields: &Fields,
+    transform: &mut TransformText<'_>,
+) -> io::Result<String> {
+    let mut scanner = Scanner {
+        input,
+        pos: 0,
+        output: String::with_capacity(input.len()),
+        path: Vec::new(),
+        fields,
+        transform,
+    };
+    scanner.whitespace();
+    while scanner.pos < input.len() {
+        scanner.value()?;
+        scanner.whitespace();
+    }
+    Ok(scanner.output)
+}
+
+/// State while scanning through a document.
+struct Scanner<'a, 't> {
+    input: &'a str,
+    pos: usize,
+    output: String,
+    path: Vec<PathElem>,
+    fields: &'a Fields,
+    transform: &'a mut TransformText<'t>,
+}
+
+impl Scanner<'_, '_> {
+    /// Scan a single value of any type.
+    fn value(&mut self) -> io::Result<()> {
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string(false).map(|_| ()),
+            Some(c) if c == '-' || c.is_ascii_alphanumeric() => {
+                // Numbers, true, false, null: just pass these through.
+                let len = self
+                    .rest()
+                    .find(|c: char| !c.is_ascii_alphanumeric() && !"+-.".contains(c));
+                self.copy(len.unwrap_or(self.rest().len()));
+                Ok(())
+            }
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    /// Scan an object, tracking the key of each member in the path.
+    fn object(&mut self) -> io::Result<()> {
+        self.copy(1);
+        self.whitespace();
+        if self.peek() == Some('}') {
+            self.copy(1);
+            return Ok(());
+        }
+        loop {
+            if self.peek() != Some('"') {
+                return Err(self.error("expected a key"));
+            }
+            let key = self.string(true)?;
+            self.path.push(PathElem::Key(key));
+            self.whitespace();
+            self.expect(':')?;
+            self.whitespace();
+            self.value()?;
+            self.path.pop();
+            self.whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.copy(1);
+                    self.whitespace();
+                }
+                Some('}') => {
+                    self.copy(1);
+                    return Ok(());
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    /// Scan an array, tracking the index of each element in the path.
+    fn array(&mut self) -> io::Result<()> {
+        self.copy(1);
+        self.whitespace();
+        if self.peek() == Some(']') {
+            self.copy(1);
+            return Ok(());
+        }
+        for index in 0.. {
+            self.path.push(PathElem::Index(index));
+            self.value()?;
+            self.path.pop();
+            self.whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.copy(1);
+                    self.whitespace();
+                }
+                Some(']') => {
+                    self.copy(1);
+                    break;
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+        Ok(())
+    }
+
+    /// Scan a string (either a value, or an object key), outputting it
+    /// (transformed if selected), and returning its decoded contents.
+    fn string(&mut self, is_key: bool) -> io::Result<String> {
+        self.copy(1);
+        let start = self.pos;
+        let mut decoded = Decoded::default().with_quoting(Quoting::Backslashes);
+        let mut chars = self.rest().char_indices();
+        let end = loop {
+            let Some((offset, c)) = chars.next() else {
+                return Err(self.error("unterminated string"));
+            };
+            match c {
+                '"' => break offset,
+                '\\' => {
+                    let escaped = match chars.next() {
+                        Some((_, 'b')) => '\u{8}',
+                        Some((_, 'f')) => '\u{c}',
+                        Some((_, 'n')) => '\n',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 't')) => '\t',
+                        Some((_, 'u')) => {
+                            let code = hex4(chars.as_str())
+                                .ok_or_else(|| self.error("bad \\u escape"))?;
+                            chars.nth(3);
+                            // Surrogate pairs take a second escape to decode
+                            // properly. Lone surrogates can't be represented in
+                            // a `String`, so just become U+FFFD.
+                            let low = chars.as_str().strip_prefix("\\u").and_then(hex4);
+                            match (code, low) {
+                                (0xd800..=0xdbff, Some(low @ 0xdc00..=0xdfff)) => {
+                                    chars.nth(5);
+                                    char::from_u32(
+                                        0x10000
+                                            + ((code - 0xd800) << 10)
+                                            + (low - 0xdc00),
+                                    )
+                                    .unwrap_or('\u{fffd}')
+                                }
+                                _ => char::from_u32(code).unwrap_or('\u{fffd}'),
+                            }
+                        }
+                        Some((_, c)) => c,
+                        None => return Err(self.error("unterminated string")),
+                    };
+                    decoded.push(escaped, offset);
+                }
+                c => decoded.push(c, offset),
+            }
+        };
+        decoded.finish(end);
+
+        // A key is selected according to the path of its own member.
+        let selected = if is_key {
+            self.path.push(PathElem::Key(decoded.text.clone()));
+            let selected = self.fields.keys && self.fields.selects(&self.path);
+            self.path.pop();
+            selected
+        } else {
+            self.fields.selects(&self.path)
+        };
+        let raw = &self.input[start..start + end];
+        if selected {
+            let replacements = (self.transform)(&decoded.text);
+            let spliced = decoded
+                .splice(raw, &replacements)
+                .map_err(|message| self.error(&message))?;
+            self.output.push_str(&spliced);
+        } else {
+            self.output.push_str(raw);
+        }
+        self.pos = start + end;
+        self.copy(1);
+        Ok(decoded.text)
+    }
+
+    /// Pass through any whitespace.
+    fn whitespace(&mut self) {
+        let len = self.rest().find(|c: char| !c.is_ascii_whitespace());
+        self.copy(len.unwrap_or(self.rest().len()));
+    }
+
+    /// Pass through an expected character.
+    fn expect(&mut self, c: char) -> io::Result<()> {
+        if self.peek() == Some(c) {
+            self.copy(1);
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    /// Pass through some number of bytes.
+    fn copy(&mut self, len: usize) {
+        self.output.push_str(&self.input[self.pos..self.pos + len]);
+        self.pos += len;
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// An error at the current position.
+    fn error(&self, message: &str) -> io::Error {
+        let line = self.input[..self.pos].matches('\n').count() + 1;
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid JSON at line {}: {}", line, message),
+        )
+    }
+}
+
+/// The code unit given by the four hex digits at the start of a `\u` escape
+/// (which, unlike `from_str_radix`, doesn't allow a sign).
+fn hex4(s: &str) -> Option<u32> {
+    let hex = s
+        .get(..4)
+        .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))?;
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::transform;
+    use crate::structured::{Fields, mark, replace_all};
+
+    #[test]
+    fn values_only() {
+        let input =
+            "{ \"xxx\": [\"xxx\", 1.5e3, true, null,\n  {\"a\" :\"\\/xxx\\u0078\"}] }\n";
+        assert_eq!(
+            mark(transform, input, &Fields::default()),
+            "{ \"xxx\": [\"<xxx>\", 1.5e3, true, null,\n  {\"a\" :\"\\/<xxxx>\"}] }\n"
+        );
+    }
+
+    #[test]
+    fn keys_and_fields() {
+        let input = r#"{"xxx": {"xxx": "xxx"}, "b": "xxx"}"#;
+        let keys = Fields {
+            keys: true,
+            ..Fields::default()
+        };
+        assert_eq!(
+            mark(transform, input, &keys),
+            r#"{"<xxx>": {"<xxx>": "<xxx>"}, "b": "<xxx>"}"#
+        );
+        let fields = Fields {
+            include: vec!["$.xxx.xxx".parse().unwrap()],
+            ..Fields::default()
+        };
+        assert_eq!(
+            mark(transform, input, &fields),
+            r#"{"xxx": {"xxx": "<xxx>"}, "b": "xxx"}"#
+        );
+    }
+
+    #[test]
+    fn json_lines() {
+        let input = "{\"a\": \"xxx\"}\n{\"a\": \"yyy\"}\n\"xxx\"\n";
+        assert_eq!(
+            mark(transform, input, &Fields::default()),
+            "{\"a\": \"<xxx>\"}\n{\"a\": \"yyy\"}\n\"<xxx>\"\n"
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(
+            replace_all(transform, r#"{"xxx": "Axxx"}"#, "a\"b\\c\n").unwrap(),
+            r#"{"a\"b\\c\n": "Aa\"b\\c\n"}"#
+        );
+    }
+
+    #[test]
+    fn errors() {
+        for bad in [
+            "{\"a\" 1}",
+            "[1, 2",
+            "\"abc",
+            "{1: 2}",
+            "@",
+            "\"\\u+041\"",
+            "\"\\u12\"",
+        ] {
+            let mut identity = |_: &str| Vec::new();
+            assert!(
+                transform(bad, &Fields::default(), &mut identity).is_err(),
+                "{}",
+                bad
+            );
+        }
+    }
+}
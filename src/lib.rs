@@ -0,0 +1,32 @@
+//! Identify substrings that look like cryptographic hashes/GUIDs/UUIDs/etc
+//! in text, and optionally randomize them. This is the library behind the
+//! `hashmash` executable, for use with eg in-house classifiers: see
+//! `Transformer::with_classifier`.
+
+#![warn(
+    missing_debug_implementations,
+    rust_2018_idioms,
+    missing_docs,
+    unused_crate_dependencies,
+    unused
+)]
+#![deny(clippy::all)]
+
+mod audit;
+mod base64;
+mod blocks;
+pub mod classify;
+pub mod config;
+pub mod encrypt;
+mod json;
+mod lockfile;
+pub mod model;
+pub mod opts;
+pub mod pseudonym;
+mod randomize;
+pub mod replace;
+mod structured;
+mod tokens;
+mod toml;
+pub mod transform;
+mod yaml;
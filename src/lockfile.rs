@@ -0,0 +1,230 @@
+//! Format-aware processing of common lockfiles. Here we know exactly which
+//! fields hold hashes, so rather than relying on the pre-classifier and trigram
+//! heuristics, we pick out just those values and leave package names, versions
+//! etc completely alone.
+
+use clap::ValueEnum;
+use regex::{Captures, Regex};
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Supported lockfile formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum LockfileFormat {
+    /// Infer the format from the filename
+    Auto,
+    /// Rust `Cargo.lock`
+    Cargo,
+    /// Nix `flake.lock`
+    Flake,
+    /// npm `package-lock.json` (or `npm-shrinkwrap.json`)
+    Npm,
+    /// `yarn.lock`
+    Yarn,
+    /// `pnpm-lock.yaml`
+    Pnpm,
+    /// Go `go.sum`
+    GoSum,
+}
+
+impl LockfileFormat {
+    /// Resolve `Auto` into a concrete format, based on the filename (if any).
+    pub fn resolve(self, filename: Option<&str>) -> Option<Self> {
+        if self != LockfileFormat::Auto {
+            return Some(self);
+        }
+        let basename = Path::new(filename?).file_name()?.to_str()?;
+        match basename {
+            "Cargo.lock" => Some(LockfileFormat::Cargo),
+            "flake.lock" => Some(LockfileFormat::Flake),
+            "package-lock.json" | "npm-shrinkwrap.json" => Some(LockfileFormat::Npm),
+            "yarn.lock" => Some(LockfileFormat::Yarn),
+            "pnpm-lock.yaml" => Some(LockfileFormat::Pnpm),
+            "go.sum" => Some(LockfileFormat::GoSum),
+            _ => None,
+        }
+    }
+
+    /// The set of rules for this format. Each regex has a `hash` capture group
+    /// covering exactly the part to transform: any algorithm prefix (eg
+    /// `sha512-`, `h1:`) and surrounding quotes are left outside it.
+    fn rules(self) -> &'static [Regex] {
+        match self {
+            LockfileFormat::Auto => &[],
+            LockfileFormat::Cargo => &CARGO,
+            LockfileFormat::Flake => &FLAKE,
+            LockfileFormat::Npm => &NPM,
+            LockfileFormat::Yarn => &YARN,
+            LockfileFormat::Pnpm => &PNPM,
+            LockfileFormat::GoSum => &GO_SUM,
+        }
+    }
+
+    /// Transform just the integrity fields of a line, using the supplied
+    /// function on each hash value. Everything else is passed through verbatim.
+    pub fn transform_line(
+        self,
+        line: &str,
+        mut transform: impl FnMut(&str) -> String,
+    ) -> String {
+        let mut line = line.to_string();
+        for rule in self.rules() {
+            line = rule
+                .replace_all(&line, |captures: &Captures<'_>| {
+                    let all = captures.get(0).expect("regex match 0");
+                    let hash = captures.name("hash").expect("regex match hash");
+                    format!(
+                        "{}{}{}",
+                        &all.as_str()[..hash.start() - all.start()],
+                        transform(hash.as_str()),
+                        &all.as_str()[hash.end() - all.start()..]
+                    )
+                })
+                .into_owned();
+        }
+        line
+    }
+}
+
+/// Shorthand for the base64 alphabet (including padding) used by SRI-style
+/// integrity values.
+macro_rules! b64 {
+    () => {
+        r"[A-Za-z0-9+/]+=*"
+    };
+}
+
+/// Compile a list of rule regexes.
+fn compile(patterns: &[&str]) -> Vec<Regex> {
+    patterns.iter().map(|p| Regex::new(p).unwrap()).collect()
+}
+
+static CARGO: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    compile(&[
+        r#"^checksum = "(?P<hash>[0-9a-f]+)""#,
+        r##"^source = "git\+[^"#]*#(?P<hash>[0-9a-f]{7,})""##,
+    ])
+});
+
+static FLAKE: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    compile(&[
+        concat!(r#""narHash": "sha\d+-(?P<hash>"#, b64!(), r#")""#),
+        r#""rev": "(?P<hash>[0-9a-f]{7,})""#,
+    ])
+});
+
+static NPM: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    compile(&[
+        concat!(r#""integrity": "sha\d+-(?P<hash>"#, b64!(), r#")""#),
+        r##""resolved": "git\+[^"#]*#(?P<hash>[0-9a-f]{7,})""##,
+    ])
+});
+
+static YARN: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    compile(&[
+        concat!(r#"^\s+integrity "?sha\d+-(?P<hash>"#, b64!(), ")"),
+        r##"^\s+resolved "[^"#]*#(?P<hash>[0-9a-f]{7,})""##,
+        r#"^\s+checksum: (?P<hash>[0-9a-f]+)"#,
+    ])
+});
+
+static PNPM: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    compile(&[
+        concat!(r#"integrity: "?sha\d+-(?P<hash>"#, b64!(), ")"),
+        r"commit: (?P<hash>[0-9a-f]{7,})",
+    ])
+});
+
+static GO_SUM: LazyLock<Vec<Regex>> =
+    LazyLock::new(|| compile(&[concat!(r"^\S+ \S+ h1:(?P<hash>", b64!(), ")")]));
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::LockfileFormat;
+
+    /// Mark each hash so we can see exactly what was picked out.
+    fn mark(format: LockfileFormat, line: &str) -> String {
+        format.transform_line(line, |s| format!("<{}>", s))
+    }
+
+    #[test]
+    fn resolve() {
+        let auto = LockfileFormat::Auto;
+        assert_eq!(
+            auto.resolve(Some("a/b/Cargo.lock")),
+            Some(LockfileFormat::Cargo)
+        );
+        assert_eq!(auto.resolve(Some("go.sum")), Some(LockfileFormat::GoSum));
+        assert_eq!(auto.resolve(Some("README.md")), None);
+        assert_eq!(auto.resolve(None), None);
+        let npm = LockfileFormat::Npm;
+        assert_eq!(npm.resolve(None), Some(LockfileFormat::Npm));
+    }
+
+    #[test]
+    fn cargo() {
+        let cargo = LockfileFormat::Cargo;
+        assert_eq!(
+            mark(cargo, "version = \"1.0.4\"\n"),
+            "version = \"1.0.4\"\n"
+        );
+        assert_eq!(
+            mark(cargo, "checksum = \"ac3b87c63620426dd9b9\"\n"),
+            "checksum = \"<ac3b87c63620426dd9b9>\"\n"
+        );
+    }
+
+    #[test]
+    fn flake() {
+        let flake = LockfileFormat::Flake;
+        assert_eq!(
+            mark(
+                flake,
+                r#"  "narHash": "sha256-kz7f2/Ab+Q1hXQ9sN0tNF5c8Ul5Q=","#
+            ),
+            r#"  "narHash": "sha256-<kz7f2/Ab+Q1hXQ9sN0tNF5c8Ul5Q=>","#
+        );
+        assert_eq!(
+            mark(
+                flake,
+                r#"  "rev": "9d0c3ffe6783d59b427d018e8341e0084737fde9","#
+            ),
+            r#"  "rev": "<9d0c3ffe6783d59b427d018e8341e0084737fde9>","#
+        );
+        assert_eq!(
+            mark(flake, r#"  "repo": "nixpkgs","#),
+            r#"  "repo": "nixpkgs","#
+        );
+    }
+
+    #[test]
+    fn npm_yarn_pnpm() {
+        assert_eq!(
+            mark(LockfileFormat::Npm, r#""integrity": "sha512-ab+c/1==","#),
+            r#""integrity": "sha512-<ab+c/1==>","#
+        );
+        assert_eq!(
+            mark(LockfileFormat::Yarn, "  integrity sha512-ab+c/1==\n"),
+            "  integrity sha512-<ab+c/1==>\n"
+        );
+        assert_eq!(
+            mark(
+                LockfileFormat::Pnpm,
+                "resolution: {integrity: sha512-ab+c/1==}"
+            ),
+            "resolution: {integrity: sha512-<ab+c/1==>}"
+        );
+    }
+
+    #[test]
+    fn go_sum() {
+        assert_eq!(
+            mark(
+                LockfileFormat::GoSum,
+                "golang.org/x/text v0.3.7 h1:olpwvP2K=\n"
+            ),
+            "golang.org/x/text v0.3.7 h1:<olpwvP2K=>\n"
+        );
+    }
+}
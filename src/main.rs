@@ -1,27 +1,24 @@
 //! Identify substrings that look like cryptographic hashes/GUIDs/UUIDs/etc
 //! in text files, and optionally randomize them (for, eg, documentation).
 
-#![warn(
-    missing_debug_implementations,
-    rust_2018_idioms,
-    missing_docs,
-    unused_crate_dependencies,
-    unused
-)]
+#![warn(missing_debug_implementations, rust_2018_idioms, missing_docs, unused)]
 #![deny(clippy::all)]
 
 use clap::Parser;
 use std::env;
 use std::fs::{self, File};
-use std::io::{self, BufReader, BufWriter};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
 use std::process;
 
-mod classify;
-mod opts;
-mod randomize;
-mod transform;
-use opts::Opts;
-use transform::Transformer;
+use hashmash::classify::{Classifier, KnownFormats, Trigram};
+use hashmash::config::Config;
+use hashmash::encrypt::Cipher;
+use hashmash::model::{Counts, Model};
+use hashmash::opts::Opts;
+use hashmash::pseudonym::Pseudonymizer;
+use hashmash::replace::{Decrypt, Encrypt, Pseudonymize};
+use hashmash::transform::Transformer;
 
 /// App entrypoint. Just some basic processing, then jump to a `Result` context.
 pub fn main() {
@@ -40,6 +37,14 @@ pub fn main() {
         }
         opts.replace = true;
     }
+    if opts.encrypt.is_some()
+        || opts.decrypt.is_some()
+        || opts.pseudonym_key.is_some()
+        || opts.mask.is_some()
+        || opts.audit.is_some()
+    {
+        opts.replace = true;
+    }
 
     // Do the rest in a Result context.
     if let Err(err) = process(opts) {
@@ -50,31 +55,82 @@ pub fn main() {
 
 /// Bulk of the app logic.
 fn process(opts: Opts) -> io::Result<()> {
+    if let Some(model) = &opts.train {
+        return train(&opts.input_file, model);
+    }
+
     // Get an output buffer for stdout.
     let stdout_handle = io::stdout();
     let mut stdout = stdout_handle.lock();
-    // Get a transformer object to transform inputs to outputs based on opts.
-    let mut transformer = Transformer::new(&opts);
+    // Get a transformer object to transform inputs to outputs based on opts
+    // and any configuration file.
+    let config = Config::load(opts.config.as_deref(), &opts.allowlist)?;
+    let mut transformer = Transformer::new(&opts, config);
+    if let Some(model) = &opts.model {
+        let model = Model::load(model)?;
+        transformer = transformer.with_classifier(KnownFormats.or(Trigram::new(model)));
+    }
+    if let Some(key) = &opts.encrypt {
+        transformer = transformer.with_replacer(Encrypt(Cipher::new(&read_key(key)?)));
+    }
+    if let Some(key) = &opts.decrypt {
+        transformer = transformer.with_replacer(Decrypt(Cipher::new(&read_key(key)?)));
+    }
+    if let Some(key) = &opts.pseudonym_key {
+        let pseudonymizer = Pseudonymizer::new(&read_key(key)?);
+        transformer = transformer.with_replacer(Pseudonymize(pseudonymizer));
+    }
 
     if opts.input_file.is_empty() {
         //
         // No filenames, so just stdin -> stdout.
         //
         let stdin_handle = io::stdin();
-        let stdin = stdin_handle.lock();
-        transformer.run(stdin, &mut stdout)?;
+        let mut stdin = stdin_handle.lock();
+        if opts.consistent || opts.audit.is_some() {
+            // Two passes, so it has to be read up front (as the output of
+            // --audit isn't streamed anyway, it may as well be).
+            let mut input = Vec::new();
+            stdin.read_to_end(&mut input)?;
+            if opts.consistent {
+                transformer.collect(None, &input[..])?;
+            } else {
+                transformer.prescan(None, &input[..])?;
+            }
+            transformer.run(None, &input[..], &mut stdout)?;
+        } else {
+            // Streamed (eg from `tail -f`), so replacements can only avoid the
+            // originals met so far.
+            transformer.run(None, stdin, &mut stdout)?;
+        }
     } else {
         //
         // Specified filenames.
         //
+        if opts.consistent || opts.replace {
+            // First pass: find the hashes in all the files, to be replaced
+            // consistently, or at least not used as replacements.
+            for filename in &opts.input_file {
+                let file = File::open(filename)?;
+                if opts.consistent {
+                    transformer.collect(Some(filename), BufReader::new(file))?;
+                } else {
+                    transformer.prescan(Some(filename), BufReader::new(file))?;
+                }
+            }
+        }
         for filename in &opts.input_file {
+            // No point rewriting an ignored file in-place.
+            if opts.in_place && transformer.ignores(filename) {
+                continue;
+            }
             let file = File::open(filename)?;
             let input_file = BufReader::new(file);
             if !opts.in_place {
                 //
                 // Not transform-in-place, so just send output to stdout.
                 //
-                transformer.run(input_file, &mut stdout)?;
+                transformer.run(Some(filename), input_file, &mut stdout)?;
             } else {
                 //
                 // Transform in-place. Write to a temporary file, then atomically rename
@@ -85,13 +141,20 @@ fn process(opts: Opts) -> io::Result<()> {
                 // filesystem as the target. We do it this way (vs eg `mkstemp`)
                 // as a cheap way to achieve Windows compatibility.
                 let temp_filename = format!("{}~~~hashmash-{}", filename, process::id());
-                {
+                let result = {
                     // Create a scope just for fastidiousness (not required) so the
                     // temporary file is closed before we rename it.
                     let output_file = File::create(&temp_filename)?;
                     let mut output_file = BufWriter::new(output_file);
-                    transformer.run(input_file, &mut output_file)?;
+                    transformer
+                        .run(Some(filename), input_file, &mut output_file)
+                        .and_then(|()| output_file.flush())
+                };
+                if result.is_err() {
+                    // Eg a failed --audit: leave the original alone.
+                    let _ = fs::remove_file(&temp_filename);
                 }
+                result?;
                 fs::rename(temp_filename, filename)?;
                 eprintln!("Edited file {}", filename);
             }
@@ -99,3 +162,42 @@ fn process(opts: Opts) -> io::Result<()> {
     }
     Ok(())
 }
+
+/// Read a key file, ignoring any trailing newline.
+fn read_key(path: &Path) -> io::Result<Vec<u8>> {
+    let mut key = fs::read(path)?;
+    while key.last().is_some_and(|b| b.is_ascii_whitespace()) {
+        key.pop();
+    }
+    if key.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: empty key", path.display()),
+        ));
+    }
+    Ok(key)
+}
+
+/// Train a trigram model on the input files (or stdin), and write it out.
+fn train(input_files: &[String], output: &Path) -> io::Result<()> {
+    let mut counts = Counts::default();
+    if input_files.is_empty() {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        counts.add(&text);
+    }
+    for filename in input_files {
+        counts.add(&fs::read_to_string(filename)?);
+    }
+    let model = counts.model().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "no letter trigrams in the input",
+        )
+    })?;
+    let mut writer = BufWriter::new(File::create(output)?);
+    model.write(&mut writer)?;
+    writer.flush()?;
+    eprintln!("Wrote model {}", output.display());
+    Ok(())
+}
@@ -1,8 +1,14 @@
 //! Type defining the command-line arguments (help text comes from docstrings).
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::lockfile::LockfileFormat;
+use crate::model::Language;
+use crate::structured::FieldPath;
 
 /// Highlight or randomize strings that look like cryptographic hashes or GUIDs
-#[derive(Parser)]
+#[derive(Debug, Parser)]
 #[clap(version)]
 pub struct Opts {
     /// Actually randomize any identified hashes, don't just highlight them
@@ -18,6 +24,185 @@ pub struct Opts {
     #[clap(short, long)]
     pub debug: bool,
 
+    /// Treat input as a lockfile, and only transform its integrity fields (the
+    /// format is inferred from the filename if not given)
+    #[clap(long, value_enum, value_name = "FORMAT", num_args = 0..=1, require_equals = true,
+           default_missing_value = "auto")]
+    pub lockfile: Option<LockfileFormat>,
+
+    /// Look inside base64 strings that decode to text (eg Kubernetes Secret
+    /// data), replacing any hashes within and re-encoding
+    #[clap(long)]
+    pub decode_base64: bool,
+
+    /// Format of the input: with `json` (which includes JSON Lines), `yaml` or
+    /// `toml`, only string values are considered, and the layout is preserved
+    #[clap(long, value_enum, value_name = "FORMAT", default_value = "text")]
+    pub format_in: InputFormat,
+
+    /// For structured input, only consider values at this path, eg `$.commit.sha`
+    /// or `/commit/sha` (may be repeated)
+    #[clap(long, value_name = "PATH")]
+    pub field: Vec<FieldPath>,
+
+    /// For structured input, never consider values at this path (may be
+    /// repeated)
+    #[clap(long, value_name = "PATH")]
+    pub exclude_field: Vec<FieldPath>,
+
+    /// For structured input, consider object keys as well as values
+    #[clap(long)]
+    pub keys: bool,
+
+    /// Rather than transforming the input, list what was found (with the
+    /// type of each, eg `hash`, `jwt` or `aws-access-key-id`), one per line
+    #[clap(short, long, conflicts_with_all = ["replace", "in_place"])]
+    pub list: bool,
+
+    /// Check that the output contains no fragment of at least N characters of any
+    /// replaced hash (ignoring case and line breaks), and fail if it does (implies
+    /// --replace)
+    #[clap(long, value_name = "N", num_args = 0..=1, require_equals = true,
+           default_missing_value = "6", conflicts_with = "list")]
+    pub audit: Option<usize>,
+
+    /// Rather than randomizing hashes, encrypt them with the key in FILE, keeping
+    /// the class of each character (implies --replace)
+    #[clap(long, value_name = "FILE", conflicts_with_all = ["list", "preserve_order"])]
+    pub encrypt: Option<PathBuf>,
+
+    /// Decrypt hashes encrypted with --encrypt and the key in FILE (implies
+    /// --replace)
+    #[clap(long, value_name = "FILE",
+           conflicts_with_all = ["list", "preserve_order", "encrypt", "audit"])]
+    pub decrypt: Option<PathBuf>,
+
+    /// Rather than randomizing hashes, replace each with a pseudonym derived from
+    /// an HMAC of it with the key in FILE, so it's the same anywhere the same key
+    /// is used (implies --replace)
+    #[clap(long, visible_alias = "key", value_name = "FILE",
+           conflicts_with_all = ["list", "preserve_order", "encrypt", "decrypt"])]
+    pub pseudonym_key: Option<PathBuf>,
+
+    /// Rather than randomizing hashes, mask them, keeping N characters visible at
+    /// each end (or START at the start and END at the end), eg `ac3b****b7b6`
+    /// (implies --replace)
+    #[clap(long, value_name = "N|START,END", num_args = 0..=1, require_equals = true,
+           default_missing_value = "4",
+           conflicts_with_all = ["list", "preserve_order", "encrypt", "decrypt",
+                                 "pseudonym_key"])]
+    pub mask: Option<Visible>,
+
+    /// With --mask, the character to mask with (by default `*`, or `…` with
+    /// --elide)
+    #[clap(long, value_name = "CHAR", requires = "mask", value_parser = mask_char)]
+    pub mask_char: Option<char>,
+
+    /// With --mask, replace the hidden part with a single mask character, eg
+    /// `ac3b…b7b6`
+    #[clap(long, requires = "mask")]
+    pub elide: bool,
+
+    /// Read all the input first, then replace every occurrence of each hash
+    /// found, wherever it is (even if too short to recognize on its own, eg
+    /// an abbreviated commit SHA of at least 7 characters), and always the same
+    /// way
+    #[clap(long)]
+    pub consistent: bool,
+
+    /// With --replace, keep the hashes within each file in the same order, so eg
+    /// a list sorted by hash is still sorted (each file separately, so not with
+    /// --consistent)
+    #[clap(long, conflicts_with = "consistent")]
+    pub preserve_order: bool,
+
+    /// Configuration file (by default, `.hashmash.toml` in the current
+    /// directory or the nearest ancestor that has one)
+    #[clap(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// File of known-good values, never treated as hashes: one per line, either
+    /// an exact token or a `/regex/` (may be repeated)
+    #[clap(long, value_name = "FILE")]
+    pub allowlist: Vec<PathBuf>,
+
+    /// Language of the text, which picks the built-in trigram model (`auto`
+    /// picks one per file)
+    #[clap(long, value_enum, value_name = "LANG", default_value = "en")]
+    pub lang: Language,
+
+    /// Trigram model to classify with, rather than a built-in one (see --train)
+    #[clap(long, value_name = "FILE", conflicts_with = "lang")]
+    pub model: Option<PathBuf>,
+
+    /// Rather than transforming the input, count the letter trigrams in it (eg
+    /// your own docs, or non-English text), and write a model to FILE for use
+    /// with --model
+    #[clap(long, value_name = "FILE", conflicts_with_all = ["replace", "in_place", "list"])]
+    pub train: Option<PathBuf>,
+
     /// File(s) to process, or standard input if omited
     pub input_file: Vec<String>,
 }
+
+/// Supported input formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    /// Arbitrary text, processed line by line
+    Text,
+    /// JSON, or JSON Lines
+    Json,
+    /// YAML (including multiple documents)
+    Yaml,
+    /// TOML
+    Toml,
+}
+
+/// How many characters of a masked hash are visible at each end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Visible {
+    /// At the start
+    pub start: usize,
+    /// At the end
+    pub end: usize,
+}
+
+impl FromStr for Visible {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| {
+            n.trim()
+                .parse()
+                .map_err(|_| format!("expected N or START,END, not `{}`", s))
+        };
+        match s.split_once(',') {
+            Some((start, end)) => Ok(Visible {
+                start: parse(start)?,
+                end: parse(end)?,
+            }),
+            None => {
+                let n = parse(s)?;
+                Ok(Visible { start: n, end: n })
+            }
+        }
+    }
+}
+
+/// Parse a mask character, which mustn't be whitespace, a control character, a
+/// quote or a backslash, any of which could break the syntax of the output.
+fn mask_char(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None)
+            if !c.is_whitespace() && !c.is_control() && !"\"'`\\".contains(c) =>
+        {
+            Ok(c)
+        }
+        (Some(c), None) => Err(format!(
+            "{:?} would need escaping in the output, so can't be used",
+            c
+        )),
+        _ => Err(format!("expected a single character, not `{}`", s)),
+    }
+}
@@ -1,19 +1,22 @@
 //! Randomize a string, while maintaining character classes.
-use rand::rngs::ThreadRng;
-use rand::{Rng, rng};
+use rand::Rng;
+use rand::seq::index;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use crate::replace::MAX_ATTEMPTS;
 
 /// Randomize a string, while preserving major character classes. eg
 /// "123-abc_DEF" might go to "973-qox_NAP". It doesn't matter that the random
 /// number generation isn't cryptographic quality - from a security perspective,
 /// it could randomize everyting to '000-aaa_AAA" and that would be fine.
-pub fn randomize(input: &str) -> String {
-    let mut rng = rng();
+pub fn randomize(input: &str, rng: &mut impl Rng) -> String {
     let mut output = String::with_capacity(input.len());
     for c in input.chars() {
         let new_c = None // Just to align next few lines
-            .or_else(|| try_range('0', '9', c, &mut rng))
-            .or_else(|| try_range('a', 'z', c, &mut rng))
-            .or_else(|| try_range('A', 'Z', c, &mut rng))
+            .or_else(|| try_range('0', '9', c, rng))
+            .or_else(|| try_range('a', 'z', c, rng))
+            .or_else(|| try_range('A', 'Z', c, rng))
             .unwrap_or(c);
         output.push(new_c);
     }
@@ -21,7 +24,7 @@ pub fn randomize(input: &str) -> String {
 }
 
 /// Replace a char with a random alternative if within the specified range.
-fn try_range(low: char, high: char, c: char, rng: &mut ThreadRng) -> Option<char> {
+fn try_range(low: char, high: char, c: char, rng: &mut impl Rng) -> Option<char> {
     if c < low || c > high {
         None
     } else {
@@ -29,20 +32,158 @@ fn try_range(low: char, high: char, c: char, rng: &mut ThreadRng) -> Option<char
     }
 }
 
+/// Randomize a set of strings, preserving character classes as `randomize`
+/// does, and also their order: if one string sorts before another, so do their
+/// replacements. Strings with a common prefix get replacements with a common
+/// prefix, but otherwise distinct strings get distinct replacements. Where
+/// there's any choice, the replacement for each string is one that `accept`s
+/// (given the original and the replacement), if one can be found.
+pub fn randomize_sorted(
+    inputs: &[&str],
+    rng: &mut impl Rng,
+    accept: &impl Fn(&str, &str) -> bool,
+) -> HashMap<String, String> {
+    let mut inputs: Vec<Vec<char>> = inputs.iter().map(|s| s.chars().collect()).collect();
+    inputs.sort();
+    inputs.dedup();
+    let mut outputs = inputs.clone();
+    randomize_sorted_from(&inputs, &mut outputs, 0, rng, accept);
+    inputs
+        .into_iter()
+        .zip(outputs)
+        .map(|(input, output)| {
+            (input.into_iter().collect(), output.into_iter().collect())
+        })
+        .collect()
+}
+
+/// Randomize the characters from position `depth` onwards of some sorted
+/// strings, which all have the same characters before that.
+fn randomize_sorted_from(
+    inputs: &[Vec<char>],
+    outputs: &mut [Vec<char>],
+    depth: usize,
+    rng: &mut impl Rng,
+    accept: &impl Fn(&str, &str) -> bool,
+) {
+    // Once there's just one string left, the rest of it is free: try for one
+    // that's acceptable.
+    if let ([input], [output]) = (inputs, &mut *outputs) {
+        let original: String = input.iter().collect();
+        let prefix: String = output[..depth].iter().collect();
+        let rest: String = input[depth..].iter().collect();
+        for _ in 0..MAX_ATTEMPTS {
+            let replaced = prefix.clone() + &randomize(&rest, rng);
+            *output = replaced.chars().collect();
+            if accept(&original, &replaced) {
+                break;
+            }
+        }
+        return;
+    }
+    // Strings that end here sort first, and are done. Then group by the next
+    // character, keeping the groups in the same order.
+    let mut start = inputs.iter().take_while(|s| s.len() == depth).count();
+    let mut groups = Vec::new();
+    while start < inputs.len() {
+        let c = inputs[start][depth];
+        let end = start + inputs[start..].iter().take_while(|s| s[depth] == c).count();
+        groups.push((c, start..end));
+        start = end;
+    }
+    let chars: Vec<char> = groups.iter().map(|(c, _)| *c).collect();
+    let new_chars = randomize_sorted_chars(&chars, rng);
+    for ((_, range), new_c) in groups.into_iter().zip(new_chars) {
+        for output in &mut outputs[range.clone()] {
+            output[depth] = new_c;
+        }
+        randomize_sorted_from(
+            &inputs[range.clone()],
+            &mut outputs[range],
+            depth + 1,
+            rng,
+            accept,
+        );
+    }
+}
+
+/// Replace some distinct characters, in order, with distinct random ones of
+/// the same classes, in the same order.
+fn randomize_sorted_chars(chars: &[char], rng: &mut impl Rng) -> Vec<char> {
+    let mut output = chars.to_vec();
+    for class in [CLASS_DIGIT, CLASS_LOWER, CLASS_UPPER] {
+        let positions: Vec<usize> = (0..chars.len())
+            .filter(|&i| class.contains(&chars[i]))
+            .collect();
+        let size = *class.end() as usize - *class.start() as usize + 1;
+        let mut picked = index::sample(rng, size, positions.len()).into_vec();
+        picked.sort();
+        for (i, offset) in positions.into_iter().zip(picked) {
+            output[i] = char::from(*class.start() as u8 + offset as u8);
+        }
+    }
+    output
+}
+
+/// The character classes that are randomized.
+const CLASS_DIGIT: RangeInclusive<char> = '0'..='9';
+const CLASS_LOWER: RangeInclusive<char> = 'a'..='z';
+const CLASS_UPPER: RangeInclusive<char> = 'A'..='Z';
+
+/// The number of different strings `randomize` could produce for an input
+/// (saturating, for long inputs).
+pub fn variants(input: &str) -> u128 {
+    input
+        .chars()
+        .map(|c| class_size(c).unwrap_or(1))
+        .fold(1, u128::saturating_mul)
+}
+
+/// The `n`th of the strings `randomize` could produce for an input, counting
+/// from `0` up to `variants(input)`, in order.
+pub fn nth_variant(input: &str, n: u128) -> String {
+    let mut n = n;
+    let mut output: Vec<char> = input.chars().collect();
+    for c in output.iter_mut().rev() {
+        if let Some(size) = class_size(*c) {
+            let low = if c.is_ascii_digit() {
+                b'0'
+            } else if c.is_ascii_lowercase() {
+                b'a'
+            } else {
+                b'A'
+            };
+            *c = char::from(low + (n % size) as u8);
+            n /= size;
+        }
+    }
+    output.into_iter().collect()
+}
+
+/// The number of characters in the class of a character, if it's randomized.
+fn class_size(c: char) -> Option<u128> {
+    match c {
+        '0'..='9' => Some(10),
+        'a'..='z' | 'A'..='Z' => Some(26),
+        _ => None,
+    }
+}
+
 /// Tests
 #[cfg(test)]
 mod tests {
-    use super::randomize;
+    use super::{nth_variant, randomize, randomize_sorted, variants};
+    use rand::rng;
     #[test]
     fn negative() {
         let input = "-& @!_#";
-        assert_eq!(input, randomize(input));
+        assert_eq!(input, randomize(input, &mut rng()));
     }
 
     #[test]
     fn positive() {
         let input = "0123-4567-89ab-cdef-ghij-wxyz-ABCD-EFGH-WXYZ";
-        let output = randomize(input);
+        let output = randomize(input, &mut rng());
         let mut changes = 0;
         let char_classes: &[fn(char) -> bool] = &[
             |x| x.is_ascii_digit(),
@@ -64,4 +205,37 @@ mod tests {
         }
         assert!(changes > 25);
     }
+
+    #[test]
+    fn enumerate() {
+        assert_eq!(variants("a-1"), 260);
+        assert_eq!(nth_variant("x-9", 0), "a-0");
+        assert_eq!(nth_variant("x-9", 37), "d-7");
+        assert_eq!(nth_variant("x-9", 259), "z-9");
+        assert_eq!(variants(&"f".repeat(40)), u128::MAX);
+    }
+
+    #[test]
+    fn sorted() {
+        let mut inputs = vec![
+            "0a1b2c3d", "0a1b2c3e", "0a1f", "0a1", "9ZZ-x", "9ZZ-y", "9ZZ_a", "a0", "A0",
+        ];
+        let mapping = randomize_sorted(&inputs, &mut rng(), &|_, _| true);
+        inputs.sort();
+        let outputs: Vec<&str> = inputs.iter().map(|s| mapping[*s].as_str()).collect();
+        assert!(outputs.is_sorted(), "{:?}", outputs);
+        for (input, output) in inputs.iter().zip(&outputs) {
+            // The same class at each position.
+            assert_eq!(nth_variant(input, 0), nth_variant(output, 0));
+        }
+        assert_eq!(outputs[0].len(), 3);
+        assert!(outputs[1].starts_with(outputs[0]));
+
+        // Once past the first character, `9d0c3f` can be made acceptable.
+        let inputs = ["0a1b2c", "0a1b2d", "9d0c3f"];
+        let accept = |_: &str, output: &str| output.ends_with(|c| c <= 'm');
+        let mapping = randomize_sorted(&inputs, &mut rng(), &accept);
+        assert!(mapping["9d0c3f"].ends_with(|c| c <= 'm'));
+        assert!(mapping["0a1b2c"] < mapping["0a1b2d"]);
+    }
 }
@@ -0,0 +1,501 @@
+//! Helpers shared by the structured input formats (JSON, YAML, TOML), where we
+//! only consider the contents of string values, and can restrict that further
+//! to particular fields. Everything the formats don't change is passed through
+//! byte-for-byte, so layout, ordering, escaping and comments are preserved.
+
+use std::io;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Ranges of some decoded string contents, and what to replace them with.
+pub type Replacements = Vec<(Range<usize>, String)>;
+
+/// Function computing the replacements for some decoded string contents.
+pub type TransformText<'a> = dyn FnMut(&str) -> Replacements + 'a;
+
+/// Signature shared by the structured format modules, to transform a whole
+/// document.
+pub type TransformDocument =
+    fn(&str, &Fields, &mut TransformText<'_>) -> io::Result<String>;
+
+/// One step in the location of a value within a document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathElem {
+    /// A named member of an object/mapping/table
+    Key(String),
+    /// An element of an array/sequence
+    Index(usize),
+}
+
+/// One step in a user-supplied field path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Selector {
+    /// Matches a key of this name, or an index with this decimal representation
+    Name(String),
+    /// Matches any key or index
+    Any,
+}
+
+/// A user-supplied field path, either in JSONPath-like syntax (eg
+/// `$.commit.sha`, `$.items[*].id`, `$['odd.key']`) or as a JSON pointer (eg
+/// `/commit/sha`). It selects any value at, or nested within, that location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldPath(Vec<Selector>);
+
+impl FieldPath {
+    /// Whether the value at `path` is selected by this field path.
+    fn selects(&self, path: &[PathElem]) -> bool {
+        self.0.len() <= path.len()
+            && self
+                .0
+                .iter()
+                .zip(path)
+                .all(|(selector, elem)| match (selector, elem) {
+                    (Selector::Any, _) => true,
+                    (Selector::Name(name), PathElem::Key(key)) => name == key,
+                    (Selector::Name(name), PathElem::Index(index)) => {
+                        *name == index.to_string()
+                    }
+                })
+    }
+
+    /// Parse a JSON pointer, eg `/commit/sha`.
+    fn parse_pointer(s: &str) -> Result<Self, String> {
+        let selectors = s
+            .split('/')
+            .skip(1)
+            .map(|part| match part {
+                "*" => Selector::Any,
+                _ => Selector::Name(part.replace("~1", "/").replace("~0", "~")),
+            })
+            .collect();
+        Ok(FieldPath(selectors))
+    }
+
+    /// Parse a JSONPath-like path, eg `$.items[*].id`.
+    fn parse_dotted(s: &str) -> Result<Self, String> {
+        let mut selectors = Vec::new();
+        let mut rest = s.strip_prefix('$').unwrap_or(s);
+        while !rest.is_empty() {
+            if let Some(tail) = rest.strip_prefix('.') {
+                let end = tail.find(['.', '[']).unwrap_or(tail.len());
+                let name = &tail[..end];
+                if name.is_empty() {
+                    return Err(format!("empty key in field path {}", s));
+                }
+                selectors.push(match name {
+                    "*" => Selector::Any,
+                    _ => Selector::Name(name.to_string()),
+                });
+                rest = &tail[end..];
+            } else if let Some(tail) = rest.strip_prefix('[') {
+                let end = tail
+                    .find(']')
+                    .ok_or_else(|| format!("unclosed '[' in field path {}", s))?;
+                let inner = tail[..end].trim();
+                let unquoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|i| i.strip_suffix('\''))
+                    .or_else(|| {
+                        inner.strip_prefix('"').and_then(|i| i.strip_suffix('"'))
+                    });
+                selectors.push(match (inner, unquoted) {
+                    (_, Some(name)) => Selector::Name(name.to_string()),
+                    ("*", None) => Selector::Any,
+                    (index, None) if index.parse::<usize>().is_ok() => {
+                        Selector::Name(index.to_string())
+                    }
+                    _ => {
+                        return Err(format!(
+                            "bad subscript [{}] in field path {}",
+                            inner, s
+                        ));
+                    }
+                });
+                rest = &tail[end + 1..];
+            } else {
+                return Err(format!("expected '.' or '[' in field path {}", s));
+            }
+        }
+        Ok(FieldPath(selectors))
+    }
+}
+
+impl FromStr for FieldPath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('/') {
+            FieldPath::parse_pointer(s)
+        } else if s.starts_with(['$', '.', '[']) {
+            FieldPath::parse_dotted(s)
+        } else {
+            // Allow the leading `$.` to be omitted, eg `commit.sha`
+            FieldPath::parse_dotted(&format!(".{}", s))
+        }
+    }
+}
+
+/// Which parts of a structured document to consider.
+#[derive(Clone, Debug, Default)]
+pub struct Fields {
+    /// Only consider values selected by one of these (or all, if empty)
+    pub include: Vec<FieldPath>,
+    /// Never consider values selected by any of these
+    pub exclude: Vec<FieldPath>,
+    /// Also consider object keys, not just values
+    pub keys: bool,
+}
+
+impl Fields {
+    /// Whether the value at `path` should be considered.
+    pub fn selects(&self, path: &[PathElem]) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|field| field.selects(path)))
+            && !self.exclude.iter().any(|field| field.selects(path))
+    }
+}
+
+/// How text has to be written in the raw text of a string, according to its
+/// quoting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quoting {
+    /// Within double quotes, escaped with backslashes (JSON, and YAML and TOML
+    /// double-quoted strings)
+    Backslashes,
+    /// Within single quotes, which are escaped by doubling them (YAML)
+    Doubled,
+    /// As-is, so control characters and the given characters can't be
+    /// written at all (eg within TOML literal strings, any `'`)
+    Verbatim(&'static str),
+    /// As-is, and only ASCII alphanumerics, `_` and `-` (TOML bare keys)
+    Bare,
+}
+
+impl Default for Quoting {
+    fn default() -> Self {
+        Quoting::Verbatim("")
+    }
+}
+
+impl Quoting {
+    /// Escape some decoded text, or return `None` if it can't be written.
+    pub fn escape(self, text: &str) -> Option<String> {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match (self, c) {
+                (Quoting::Backslashes, '"' | '\\') => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                (Quoting::Backslashes, '\n') => escaped.push_str("\\n"),
+                (Quoting::Backslashes, '\r') => escaped.push_str("\\r"),
+                (Quoting::Backslashes, '\t') => escaped.push_str("\\t"),
+                (Quoting::Backslashes, c) if c.is_control() => {
+                    escaped.push_str(&format!("\\u{:04x}", u32::from(c)));
+                }
+                (Quoting::Doubled, '\'') => escaped.push_str("''"),
+                (Quoting::Bare, c) if !c.is_ascii_alphanumeric() && !"_-".contains(c) => {
+                    return None;
+                }
+                (Quoting::Verbatim(forbidden), c) if forbidden.contains(c) => {
+                    return None;
+                }
+                (Quoting::Doubled | Quoting::Verbatim(_), c) if c.is_control() => {
+                    return None;
+                }
+                (_, c) => escaped.push(c),
+            }
+        }
+        Some(escaped)
+    }
+}
+
+/// The decoded contents of a quoted/escaped string in some document, along with
+/// enough information to map positions in the decoded text back to the raw text.
+#[derive(Debug, Default)]
+pub struct Decoded {
+    /// The decoded text
+    pub text: String,
+    /// How replacements have to be written in the raw text
+    pub quoting: Quoting,
+    /// For each byte offset in `text` (plus one past the end), the corresponding
+    /// offset in the raw text
+    offsets: Vec<usize>,
+}
+
+impl Decoded {
+    /// Raw text that needs no decoding, so is written verbatim.
+    pub fn plain(raw: &str) -> Self {
+        Decoded {
+            text: raw.to_string(),
+            quoting: Quoting::default(),
+            offsets: (0..=raw.len()).collect(),
+        }
+    }
+
+    /// The same, but with replacements written according to `quoting`.
+    pub fn with_quoting(self, quoting: Quoting) -> Self {
+        Decoded { quoting, ..self }
+    }
+
+    /// Decode the contents of a double-quoted string with backslash escapes,
+    /// as used (with minor variations) by YAML and TOML. This is lenient: an
+    /// unknown escape just stands for the escaped character itself.
+    pub fn backslashes(raw: &str) -> Self {
+        let mut decoded = Decoded::default().with_quoting(Quoting::Backslashes);
+        let mut chars = raw.char_indices().peekable();
+        while let Some((offset, c)) = chars.next() {
+            if c != '\\' {
+                decoded.push(c, offset);
+                continue;
+            }
+            let Some((_, escaped)) = chars.next() else {
+                decoded.push(c, offset);
+                break;
+            };
+            let hex_digits = match escaped {
+                'x' => 2,
+                'u' => 4,
+                'U' => 8,
+                _ => 0,
+            };
+            let c = match escaped {
+                '0' => '\0',
+                'a' => '\u{7}',
+                'b' => '\u{8}',
+                't' => '\t',
+                'n' => '\n',
+                'v' => '\u{b}',
+                'f' => '\u{c}',
+                'r' => '\r',
+                'e' => '\u{1b}',
+                'N' => '\u{85}',
+                '_' => '\u{a0}',
+                'L' => '\u{2028}',
+                'P' => '\u{2029}',
+                _ if hex_digits > 0 => {
+                    let start = chars.peek().map_or(raw.len(), |(i, _)| *i);
+                    let code = raw
+                        .get(start..start + hex_digits)
+                        .and_then(|hex| u32::from_str_radix(hex, 16).ok());
+                    match code {
+                        Some(code) => {
+                            for _ in 0..hex_digits {
+                                chars.next();
+                            }
+                            char::from_u32(code).unwrap_or('\u{fffd}')
+                        }
+                        None => escaped,
+                    }
+                }
+                '\n' | '\r' => {
+                    // A line-ending backslash joins lines, eating any whitespace
+                    while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                    continue;
+                }
+                _ => escaped,
+            };
+            decoded.push(c, offset);
+        }
+        decoded.finish(raw.len());
+        decoded
+    }
+
+    /// Append a decoded char that came from the raw text starting at `raw_offset`.
+    pub fn push(&mut self, c: char, raw_offset: usize) {
+        self.text.push(c);
+        self.offsets
+            .extend(std::iter::repeat_n(raw_offset, c.len_utf8()));
+    }
+
+    /// Mark the end of the raw text.
+    pub fn finish(&mut self, raw_len: usize) {
+        self.offsets.push(raw_len);
+    }
+
+    /// Rebuild the raw text with some ranges of the decoded text replaced, each
+    /// replacement escaped according to the quoting. Fails with a message if a
+    /// replacement can't be written there.
+    pub fn splice(
+        &self,
+        raw: &str,
+        replacements: &[(Range<usize>, String)],
+    ) -> Result<String, String> {
+        let mut output = String::with_capacity(raw.len());
+        let mut pos = 0;
+        for (range, replacement) in replacements {
+            let escaped = self.quoting.escape(replacement).ok_or_else(|| {
+                format!(
+                    "replacement {:?} can't be written in this string",
+                    replacement
+                )
+            })?;
+            output.push_str(&raw[pos..self.offsets[range.start]]);
+            output.push_str(&escaped);
+            pos = self.offsets[range.end];
+        }
+        output.push_str(&raw[pos..]);
+        Ok(output)
+    }
+
+    /// The decoded text with some ranges replaced.
+    pub fn replaced(&self, replacements: &[(Range<usize>, String)]) -> String {
+        let mut output = String::with_capacity(self.text.len());
+        let mut pos = 0;
+        for (range, replacement) in replacements {
+            output.push_str(&self.text[pos..range.start]);
+            output.push_str(replacement);
+            pos = range.end;
+        }
+        output.push_str(&self.text[pos..]);
+        output
+    }
+}
+
+/// For the tests of each format: run its `transform` over a document, marking
+/// every run of 3+ `x` chars in the selected strings, eg as `<xxx>`.
+#[cfg(test)]
+pub(crate) fn mark(transform: TransformDocument, input: &str, fields: &Fields) -> String {
+    let regex = regex::Regex::new("x{3,}").unwrap();
+    let mut mark = |s: &str| -> Vec<(Range<usize>, String)> {
+        regex
+            .find_iter(s)
+            .map(|m| (m.range(), format!("<{}>", m.as_str())))
+            .collect()
+    };
+    transform(input, fields, &mut mark).unwrap()
+}
+
+/// Similarly, but replacing every `xxx` in any string (including keys) with
+/// `replacement`.
+#[cfg(test)]
+pub(crate) fn replace_all(
+    transform: TransformDocument,
+    input: &str,
+    replacement: &str,
+) -> io::Result<String> {
+    let mut replace = |s: &str| -> Vec<(Range<usize>, String)> {
+        s.match_indices("xxx")
+            .map(|(i, m)| (i..i + m.len(), replacement.to_string()))
+            .collect()
+    };
+    let keys = Fields {
+        keys: true,
+        ..Fields::default()
+    };
+    transform(input, &keys, &mut replace)
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::{Decoded, FieldPath, Fields, PathElem, Quoting};
+
+    fn path(elems: &[&str]) -> Vec<PathElem> {
+        elems
+            .iter()
+            .map(|e| match e.parse() {
+                Ok(index) => PathElem::Index(index),
+                Err(_) => PathElem::Key(e.to_string()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn field_paths() {
+        let sha: FieldPath = "$.commit.sha".parse().unwrap();
+        assert_eq!(sha, "/commit/sha".parse().unwrap());
+        assert_eq!(sha, "$['commit'][\"sha\"]".parse().unwrap());
+        assert_eq!(sha, "commit.sha".parse().unwrap());
+        assert!(sha.selects(&path(&["commit", "sha"])));
+        assert!(sha.selects(&path(&["commit", "sha", "0"])));
+        assert!(!sha.selects(&path(&["commit"])));
+        assert!(!sha.selects(&path(&["name"])));
+
+        let ids: FieldPath = "$.items[*].id".parse().unwrap();
+        assert!(ids.selects(&path(&["items", "3", "id"])));
+        assert!(!ids.selects(&path(&["items", "3", "name"])));
+        assert!(
+            "$.items[0]"
+                .parse::<FieldPath>()
+                .unwrap()
+                .selects(&path(&["items", "0"]))
+        );
+
+        assert!("$.a[".parse::<FieldPath>().is_err());
+        assert!("$..a".parse::<FieldPath>().is_err());
+        assert!("$.a[b]".parse::<FieldPath>().is_err());
+    }
+
+    #[test]
+    fn include_exclude() {
+        let fields = Fields {
+            include: vec!["$.spec".parse().unwrap()],
+            exclude: vec!["$.spec.name".parse().unwrap()],
+            ..Fields::default()
+        };
+        assert!(fields.selects(&path(&["spec", "id"])));
+        assert!(!fields.selects(&path(&["spec", "name"])));
+        assert!(!fields.selects(&path(&["metadata", "id"])));
+    }
+
+    #[test]
+    fn backslashes() {
+        let decoded = Decoded::backslashes(
+            r#"a\"b\x41\u00e9\
+   c"#,
+        );
+        assert_eq!(decoded.text, "a\"bAéc");
+        let raw = r#"\x41bc"#;
+        let decoded = Decoded::backslashes(raw);
+        assert_eq!(
+            decoded.splice(raw, &[(1..3, "yz".to_string())]).unwrap(),
+            r#"\x41yz"#
+        );
+    }
+
+    #[test]
+    fn splice() {
+        // Raw `a\/bc` decodes to `a/bc`
+        let raw = r"a\/bc";
+        let mut decoded = Decoded::default();
+        decoded.push('a', 0);
+        decoded.push('/', 1);
+        decoded.push('b', 3);
+        decoded.push('c', 4);
+        decoded.finish(raw.len());
+        assert_eq!(decoded.text, "a/bc");
+        assert_eq!(decoded.splice(raw, &[]).unwrap(), raw);
+        assert_eq!(
+            decoded.splice(raw, &[(0..3, "x/y".to_string())]).unwrap(),
+            "x/yc"
+        );
+        assert_eq!(
+            decoded.splice(raw, &[(3..4, "z".to_string())]).unwrap(),
+            r"a\/bz"
+        );
+        assert_eq!(decoded.replaced(&[(3..4, "z".to_string())]), "a/bz");
+    }
+
+    #[test]
+    fn escape() {
+        let text = "a\"b\\c'd\n\u{1}";
+        assert_eq!(
+            Quoting::Backslashes.escape(text).unwrap(),
+            r#"a\"b\\c'd\n\u0001"#
+        );
+        assert_eq!(Quoting::Doubled.escape("a'b\"").unwrap(), "a''b\"");
+        assert_eq!(Quoting::Doubled.escape("a\nb"), None);
+        assert_eq!(Quoting::Verbatim("'").escape("a\"\\b").unwrap(), "a\"\\b");
+        assert_eq!(Quoting::Verbatim("'").escape("a'b"), None);
+        assert_eq!(Quoting::Bare.escape("a_b-9").unwrap(), "a_b-9");
+        assert_eq!(Quoting::Bare.escape("a*b"), None);
+
+        let raw = r#"x\u0041y"#;
+        let decoded = Decoded::backslashes(raw);
+        assert_eq!(
+            decoded.splice(raw, &[(2..3, "\"\\".to_string())]).unwrap(),
+            r#"x\u0041\"\\"#
+        );
+    }
+}
@@ -0,0 +1,472 @@
+//! TOML-aware processing. We scan the document to find each string value and
+//! its path (taking into account tables, arrays of tables, dotted keys, arrays
+//! and inline tables), and only consider those.
+
+use std::collections::HashMap;
+use std::io;
+
+use crate::structured::{Decoded, Fields, PathElem, Quoting, TransformText};
+
+/// Transform a TOML document, applying `transform` to the selected string
+/// values.
+pub fn transform(
+    input: &str,
+    fields: &Fields,
+    transform: &mut TransformText<'_>,
+) -> io::Result<String> {
+    let mut scanner = Scanner {
+        input,
+        pos: 0,
+        copied: 0,
+        output: String::with_capacity(input.len()),
+        table: Vec::new(),
+        arrays: HashMap::new(),
+        fields,
+        transform,
+    };
+    loop {
+        scanner.trivia();
+        if scanner.rest().is_empty() {
+            break;
+        }
+        if scanner.rest().starts_with('[') {
+            scanner.header()?;
+        } else {
+            let table = scanner.table.clone();
+            scanner.keyval(&table)?;
+        }
+        scanner.line_end()?;
+    }
+    scanner.emit(input.len());
+    Ok(scanner.output)
+}
+
+/// A single part of a (possibly dotted) key, with its unquoted range.
+struct KeyPart {
+    name: String,
+    start: usize,
+    end: usize,
+    quote: Option<char>,
+}
+
+/// State while scanning through a document. Positions are all byte offsets
+/// into `input`, and everything up to `copied` has been dealt with.
+struct Scanner<'a, 't> {
+    input: &'a str,
+    pos: usize,
+    copied: usize,
+    output: String,
+    /// Path of the current table
+    table: Vec<PathElem>,
+    /// Number of elements so far of each array of tables
+    arrays: HashMap<Vec<String>, usize>,
+    fields: &'a Fields,
+    transform: &'a mut TransformText<'t>,
+}
+
+impl<'a> Scanner<'a, '_> {
+    /// Process a `[table]` or `[[array.of.tables]]` header.
+    fn header(&mut self) -> io::Result<()> {
+        let array = self.rest().starts_with("[[");
+        self.pos += if array { 2 } else { 1 };
+        let keys = self.keys()?;
+        let close = if array { "]]" } else { "]" };
+        if !self.rest().starts_with(close) {
+            return Err(self.error(&format!("expected '{}'", close)));
+        }
+        self.pos += close.len();
+
+        // Any array of tables along the way refers to its latest element.
+        let mut path = Vec::new();
+        let mut prefix = Vec::new();
+        let num_keys = keys.len();
+        for (i, key) in keys.into_iter().enumerate() {
+            prefix.push(key.name.clone());
+            path.push(PathElem::Key(key.name));
+            let is_new_element = array && i + 1 == num_keys;
+            if let Some(count) = self.arrays.get(&prefix).filter(|_| !is_new_element) {
+                path.push(PathElem::Index(count - 1));
+            }
+        }
+        if array {
+            let count = self.arrays.entry(prefix.clone()).or_insert(0);
+            *count += 1;
+            path.push(PathElem::Index(*count - 1));
+            // A new element starts afresh with any nested arrays of tables.
+            self.arrays
+                .retain(|key, _| key.len() <= prefix.len() || !key.starts_with(&prefix));
+        }
+        self.table = path;
+        Ok(())
+    }
+
+    /// Process a `key = value` pair, relative to `base`.
+    fn keyval(&mut self, base: &[PathElem]) -> io::Result<()> {
+        let keys = self.keys()?;
+        let mut path = base.to_vec();
+        for key in keys {
+            path.push(PathElem::Key(key.name.clone()));
+            if self.fields.keys {
+                let raw = &self.input[key.start..key.end];
+                let decoded = decode(raw, key.quote);
+                self.scalar(&path, key.start, key.end, decoded)?;
+            }
+        }
+        self.whitespace();
+        if !self.rest().starts_with('=') {
+            return Err(self.error("expected '='"));
+        }
+        self.pos += 1;
+        self.whitespace();
+        self.value(&path)
+    }
+
+    /// Parse a (possibly dotted) key.
+    fn keys(&mut self) -> io::Result<Vec<KeyPart>> {
+        let mut keys = Vec::new();
+        loop {
+            self.whitespace();
+            let start = self.pos;
+            let quote = self
+                .rest()
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'');
+            let (start, end) = match quote {
+                Some(quote) => {
+                    let (end, next) = self.quoted(start + 1, quote)?;
+                    self.pos = next;
+                    (start + 1, end)
+                }
+                None => {
+                    let len = self
+                        .rest()
+                        .find(|c: char| {
+                            !c.is_ascii_alphanumeric() && c != '_' && c != '-'
+                        })
+                        .unwrap_or(self.rest().len());
+                    if len == 0 {
+                        return Err(self.error("expected a key"));
+                    }
+                    self.pos += len;
+                    (start, self.pos)
+                }
+            };
+            let name = decode(&self.input[start..end], quote).text;
+            keys.push(KeyPart {
+                name,
+                start,
+                end,
+                quote,
+            });
+            self.whitespace();
+            if !self.rest().starts_with('.') {
+                return Ok(keys);
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Process a value at `path`.
+    fn value(&mut self, path: &[PathElem]) -> io::Result<()> {
+        let rest = self.rest();
+        if rest.starts_with("\"\"\"") || rest.starts_with("'''") {
+            let delimiter = &rest[..3];
+            let start = self.pos + 3;
+            let end = start
+                + find_unescaped(&self.input[start..], delimiter)
+                    .ok_or_else(|| self.error("unterminated multi-line string"))?;
+            // Up to two quotes are allowed just before the delimiter.
+            let extra = self.input[end + 3..].len()
+                - self.input[end + 3..]
+                    .trim_start_matches(&delimiter[..1])
+                    .len();
+            let end = end + extra.min(2);
+            self.scalar(
+                path,
+                start,
+                end,
+                decode(&self.input[start..end], rest.chars().next()),
+            )?;
+            self.pos = end + 3;
+        } else if let Some(quote) =
+            rest.chars().next().filter(|c| *c == '"' || *c == '\'')
+        {
+            let start = self.pos + 1;
+            let (end, next) = self.quoted(start, quote)?;
+            self.scalar(
+                path,
+                start,
+                end,
+                decode(&self.input[start..end], Some(quote)),
+            )?;
+            self.pos = next;
+        } else if rest.starts_with('[') {
+            self.pos += 1;
+            let mut path = path.to_vec();
+            for index in 0.. {
+                self.trivia();
+                if self.rest().starts_with(']') {
+                    break;
+                }
+                path.push(PathElem::Index(index));
+                self.value(&path)?;
+                path.pop();
+                self.trivia();
+                if self.rest().starts_with(',') {
+                    self.pos += 1;
+                } else if !self.rest().starts_with(']') {
+                    return Err(self.error("expected ',' or ']'"));
+                }
+            }
+            self.pos += 1;
+        } else if rest.starts_with('{') {
+            self.pos += 1;
+            self.whitespace();
+            while !self.rest().starts_with('}') {
+                self.keyval(path)?;
+                self.whitespace();
+                if self.rest().starts_with(',') {
+                    self.pos += 1;
+                } else if !self.rest().starts_with('}') {
+                    return Err(self.error("expected ',' or '}'"));
+                }
+            }
+            self.pos += 1;
+        } else {
+            // Numbers, booleans, dates and times: just pass these through. Dates
+            // and times can be separated by a single space, eg `1979-05-27 07:32:00`.
+            let end = |s: &str| s.find(|c: char| ",]}#".contains(c) || c.is_whitespace());
+            let mut len = end(rest).unwrap_or(rest.len());
+            let time = rest[len..].strip_prefix(' ').filter(|time| {
+                rest[..len].len() == 10
+                    && rest.as_bytes()[4] == b'-'
+                    && time.starts_with(|c: char| c.is_ascii_digit())
+            });
+            if let Some(time) = time {
+                len += 1 + end(time).unwrap_or(time.len());
+            }
+            if len == 0 {
+                return Err(self.error("expected a value"));
+            }
+            self.pos += len;
+        }
+        Ok(())
+    }
+
+    /// Find the end of a single-line quoted string whose contents start at
+    /// `start`, returning the end of the contents and the position after the
+    /// closing quote.
+    fn quoted(&self, start: usize, quote: char) -> io::Result<(usize, usize)> {
+        let line = &self.input[start..];
+        let line = &line[..line.find('\n').unwrap_or(line.len())];
+        let delimiter = if quote == '"' { "\"" } else { "'" };
+        let end = if quote == '"' {
+            find_unescaped(line, delimiter)
+        } else {
+            line.find(quote)
+        };
+        let end = end.ok_or_else(|| self.error("unterminated string"))?;
+        Ok((start + end, start + end + 1))
+    }
+
+    /// Transform the string contents at `start..end` if `path` is selected.
+    fn scalar(
+        &mut self,
+        path: &[PathElem],
+        start: usize,
+        end: usize,
+        decoded: Decoded,
+    ) -> io::Result<()> {
+        if start >= end || !self.fields.selects(path) {
+            return Ok(());
+        }
+        let replacements = (self.transform)(&decoded.text);
+        if !replacements.is_empty() {
+            let raw = &self.input[start..end];
+            let spliced = match decoded.splice(raw, &replacements) {
+                Ok(spliced) => spliced,
+                // A bare key can always be quoted instead
+                Err(_) if decoded.quoting == Quoting::Bare => {
+                    let replaced = decoded.replaced(&replacements);
+                    let escaped =
+                        Quoting::Backslashes.escape(&replaced).unwrap_or_default();
+                    format!("\"{}\"", escaped)
+                }
+                Err(message) => return Err(self.error(&message)),
+            };
+            self.emit(start);
+            self.output.push_str(&spliced);
+            self.copied = end;
+        }
+        Ok(())
+    }
+
+    /// Skip whitespace, newlines and comments.
+    fn trivia(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if !trimmed.starts_with('#') {
+                break;
+            }
+            self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+        }
+    }
+
+    /// Skip spaces and tabs (but not newlines).
+    fn whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    }
+
+    /// Expect the end of a line, possibly after a comment.
+    fn line_end(&mut self) -> io::Result<()> {
+        self.whitespace();
+        let rest = self.rest();
+        if rest.is_empty() || rest.starts_with(['\n', '\r', '#']) {
+            Ok(())
+        } else {
+            Err(self.error("expected the end of the line"))
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    /// Pass through everything up to `pos`.
+    fn emit(&mut self, pos: usize) {
+        self.output.push_str(&self.input[self.copied..pos]);
+        self.copied = pos;
+    }
+
+    /// An error at the current position.
+    fn error(&self, message: &str) -> io::Error {
+        let line = self.input[..self.pos].matches('\n').count() + 1;
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid TOML at line {}: {}", line, message),
+        )
+    }
+}
+
+/// Find a delimiter that isn't escaped with a backslash (for `"` strings; for
+/// `'` strings this is just a plain search, since backslashes aren't special).
+fn find_unescaped(text: &str, delimiter: &str) -> Option<usize> {
+    if delimiter.starts_with('\'') {
+        return text.find(delimiter);
+    }
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if text[i..].starts_with(delimiter) {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Decode the contents of a string (or bare key), according to its quoting.
+fn decode(raw: &str, quote: Option<char>) -> Decoded {
+    let quoting = match quote {
+        Some('"') => return Decoded::backslashes(raw),
+        Some(_) => Quoting::Verbatim("'"),
+        None => Quoting::Bare,
+    };
+    Decoded::plain(raw).with_quoting(quoting)
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::transform;
+    use crate::structured::{Fields, mark, replace_all};
+
+    #[test]
+    fn values_only() {
+        let input = r#"# xxx comment
+xxx = "xxx" # xxx
+"a.xxx" = 'xxx'
+[table.xxx]
+b = ["xxx", 1, {c = "a\"xxx"}]  # xxx
+d = 1979-05-27 07:32:00
+e = """
+xxx
+"""
+"#;
+        let output = r#"# xxx comment
+xxx = "<xxx>" # xxx
+"a.xxx" = '<xxx>'
+[table.xxx]
+b = ["<xxx>", 1, {c = "a\"<xxx>"}]  # xxx
+d = 1979-05-27 07:32:00
+e = """
+<xxx>
+"""
+"#;
+        assert_eq!(mark(transform, input, &Fields::default()), output);
+    }
+
+    #[test]
+    fn fields_and_keys() {
+        let input = "\
+[package]
+name = \"xxx\"
+[[bin]]
+name = \"xxx\"
+[[bin]]
+name = \"xxx\"
+path.xxx = \"xxx\"
+";
+        let fields = Fields {
+            include: vec!["$.bin[1]".parse().unwrap()],
+            exclude: vec!["$.bin[*].name".parse().unwrap()],
+            keys: true,
+        };
+        let output = "\
+[package]
+name = \"xxx\"
+[[bin]]
+name = \"xxx\"
+[[bin]]
+name = \"xxx\"
+path.\"<xxx>\" = \"<xxx>\"
+";
+        assert_eq!(mark(transform, input, &fields), output);
+    }
+
+    #[test]
+    fn escaping() {
+        let input = "\"xxx\" = \"xxx\"\nb = 'xxx'\nc = \"\"\"\nxxx\"\"\"\n";
+        assert_eq!(
+            replace_all(transform, input, r#"a"b\c"#).unwrap(),
+            r#""a\"b\\c" = "a\"b\\c"
+b = 'a"b\c'
+c = """
+a\"b\\c"""
+"#
+        );
+        assert!(replace_all(transform, "b = 'xxx'", "a'b").is_err());
+        // Bare keys are quoted if need be
+        assert_eq!(replace_all(transform, "xxx = 1", "a_b").unwrap(), "a_b = 1");
+        assert_eq!(
+            replace_all(transform, "xxx = 1", "a'b").unwrap(),
+            "\"a'b\" = 1"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        for bad in ["a = ", "a = \"xxx", "[a", "a = [1 2]", "= 1", "a = 1 b = 2"] {
+            let mut identity = |_: &str| Vec::new();
+            assert!(
+                transform(bad, &Fields::default(), &mut identity).is_err(),
+                "{}",
+                bad
+            );
+        }
+    }
+}
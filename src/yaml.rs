@@ -0,0 +1,691 @@
+//! YAML-aware processing. This isn't a full YAML parser, but it tracks enough
+//! of the block structure (mappings, sequences, block scalars, quoting, simple
+//! flow collections) to know the path of each scalar, and to only consider
+//! scalar values rather than keys, comments or anchors.
+
+use std::io;
+
+use crate::structured::{
+    Decoded, Fields, PathElem, Quoting, Replacements, TransformText,
+};
+
+/// Transform a YAML document (or stream of documents), applying `transform` to
+/// the selected scalar values. Anything not understood is just passed through,
+/// so this only fails if a replacement can't be written where it belongs.
+pub fn transform(
+    input: &str,
+    fields: &Fields,
+    transform: &mut TransformText<'_>,
+) -> io::Result<String> {
+    let mut scanner = Scanner {
+        input,
+        copied: 0,
+        output: String::with_capacity(input.len()),
+        stack: Vec::new(),
+        pending: Pending::None,
+        fields,
+        transform,
+        error: None,
+    };
+    let mut start = 0;
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        scanner.line(start, start + content.len());
+        start += line.len();
+    }
+    scanner.emit(input.len());
+    match scanner.error {
+        Some(error) => Err(error),
+        None => Ok(scanner.output),
+    }
+}
+
+/// A block collection we're currently within.
+#[derive(Debug)]
+enum Frame {
+    /// A mapping at some indent, with the most recent key
+    Map { indent: usize, key: String },
+    /// A sequence at some indent, with the most recent index
+    Seq { indent: usize, index: usize },
+}
+
+impl Frame {
+    fn indent(&self) -> usize {
+        match self {
+            Frame::Map { indent, .. } | Frame::Seq { indent, .. } => *indent,
+        }
+    }
+}
+
+/// A scalar that may continue onto following lines.
+#[derive(Debug)]
+enum Pending {
+    None,
+    /// Block scalar (`|` or `>`), continuing while lines are more indented
+    Block {
+        parent: usize,
+    },
+    /// Plain scalar, continuing while lines are more indented (which can't
+    /// happen if the first line had to be quoted)
+    Plain {
+        parent: usize,
+        quoted: bool,
+    },
+    /// Quoted scalar, continuing until the closing quote
+    Quoted {
+        quote: char,
+    },
+}
+
+/// State while scanning through a document. Positions are all byte offsets
+/// into `input`, and everything up to `copied` has been dealt with.
+struct Scanner<'a, 't> {
+    input: &'a str,
+    copied: usize,
+    output: String,
+    stack: Vec<Frame>,
+    pending: Pending,
+    fields: &'a Fields,
+    transform: &'a mut TransformText<'t>,
+    /// The first replacement that couldn't be written
+    error: Option<io::Error>,
+}
+
+impl Scanner<'_, '_> {
+    /// Process a single line, `start..end` (excluding the line ending).
+    fn line(&mut self, start: usize, end: usize) {
+        let input = self.input;
+        let text = &input[start..end];
+        let indent = text.len() - text.trim_start_matches(' ').len();
+        let blank = text.trim().is_empty();
+
+        // First see if this line continues a scalar from a previous line.
+        match self.pending {
+            Pending::Block { parent } if blank || indent > parent => {
+                self.scalar(start + indent.min(text.len()), end, Decoded::plain);
+                return;
+            }
+            Pending::Plain { parent, quoted }
+                if !blank && indent > parent && !text[indent..].starts_with('#') =>
+            {
+                if quoted {
+                    self.fail("a plain scalar had to be quoted, but continues onto another line");
+                }
+                let content_end = start + plain_end(text, indent, false);
+                self.scalar(start + indent, content_end, |raw| {
+                    Decoded::plain(raw).with_quoting(Quoting::Verbatim("#:"))
+                });
+                return;
+            }
+            Pending::Quoted { quote } => {
+                self.quoted(start + indent, end, quote);
+                return;
+            }
+            _ => self.pending = Pending::None,
+        }
+
+        // Otherwise skip anything that isn't content.
+        let content = &text[indent..];
+        if blank || content.starts_with('#') || (indent == 0 && content.starts_with('%'))
+        {
+            return;
+        }
+        if indent == 0 && (content.starts_with("---") || content.starts_with("...")) {
+            self.stack.clear();
+            return;
+        }
+        self.node(start + indent, end, indent.saturating_sub(1));
+    }
+
+    /// Process a node starting at `pos` on a line ending at `end`. Any
+    /// continuation lines must be indented more than `parent`.
+    fn node(&mut self, pos: usize, end: usize, parent: usize) {
+        let input = self.input;
+        let text = &input[pos..end];
+        let column = pos - self.line_start(pos);
+
+        if text == "-" || text.starts_with("- ") {
+            // Sequence item
+            while self
+                .stack
+                .last()
+                .is_some_and(|frame| frame.indent() > column)
+            {
+                self.stack.pop();
+            }
+            match self.stack.last_mut() {
+                Some(Frame::Seq { indent, index }) if *indent == column => *index += 1,
+                _ => self.stack.push(Frame::Seq {
+                    indent: column,
+                    index: 0,
+                }),
+            }
+            let item =
+                pos + 1 + (text.len() - 1 - text[1..].trim_start_matches(' ').len());
+            if item < end && !self.input[item..end].starts_with('#') {
+                self.node(item, end, column);
+            }
+        } else if let Some((key_end, colon)) = find_key(text) {
+            // Mapping entry
+            while self.stack.last().is_some_and(|frame| {
+                frame.indent() > column
+                    || (frame.indent() == column && matches!(frame, Frame::Seq { .. }))
+            }) {
+                self.stack.pop();
+            }
+            let key = decode_scalar(&text[..key_end]).text;
+            match self.stack.last_mut() {
+                Some(Frame::Map { indent, key: k }) if *indent == column => *k = key,
+                _ => self.stack.push(Frame::Map {
+                    indent: column,
+                    key,
+                }),
+            }
+            if self.fields.keys {
+                match text.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let key_end = pos + key_end - 1;
+                        self.scalar(pos + 1, key_end, |raw| decode_quoted(raw, quote))
+                    }
+                    _ => {
+                        self.plain(pos, pos + key_end, false);
+                    }
+                }
+            }
+            let value = pos + colon + 1;
+            let value = value
+                + (self.input[value..end].len()
+                    - self.input[value..end].trim_start().len());
+            self.value(value, end, column);
+        } else {
+            self.value(pos, end, parent);
+        }
+    }
+
+    /// Process a value starting at `pos` on a line ending at `end`.
+    fn value(&mut self, mut pos: usize, end: usize, parent: usize) {
+        let input = self.input;
+        // Skip over any tags and anchors.
+        while input[pos..end].starts_with(['!', '&']) {
+            let text = &input[pos..end];
+            let token = text.find(' ').unwrap_or(text.len());
+            pos += token + (text[token..].len() - text[token..].trim_start().len());
+        }
+        let text = &input[pos..end];
+        match text.chars().next() {
+            None | Some('#' | '*') => {}
+            Some('|' | '>') => self.pending = Pending::Block { parent },
+            Some(quote @ ('"' | '\'')) => self.quoted(pos + 1, end, quote),
+            Some('[' | '{') if self.flow(pos, text) => {}
+            Some(_) => {
+                // Including flow collections that we can't make sense of (eg
+                // that don't fit on a line), which are treated as plain text.
+                let line_start = self.line_start(pos);
+                let stop =
+                    plain_end(&self.input[line_start..end], pos - line_start, false);
+                let quoted = self.plain(pos, line_start + stop, false);
+                self.pending = Pending::Plain { parent, quoted };
+            }
+        }
+    }
+
+    /// Process the contents of a quoted scalar starting at `pos`, through to
+    /// the closing quote (or the end of the line, if it continues).
+    fn quoted(&mut self, pos: usize, end: usize, quote: char) {
+        let text = &self.input[pos..end];
+        match closing_quote(text, quote) {
+            Some(close) => {
+                self.scalar(pos, pos + close, |raw| decode_quoted(raw, quote));
+                self.pending = Pending::None;
+            }
+            None => {
+                self.scalar(pos, end, |raw| decode_quoted(raw, quote));
+                self.pending = Pending::Quoted { quote };
+            }
+        }
+    }
+
+    /// Transform the scalar at `start..end` if its path is selected.
+    fn scalar(&mut self, start: usize, end: usize, decode: impl FnOnce(&str) -> Decoded) {
+        let Some((decoded, replacements)) = self.replacements(start, end, decode) else {
+            return;
+        };
+        match decoded.splice(&self.input[start..end], &replacements) {
+            Ok(spliced) => self.write(start, end, &spliced),
+            Err(message) => self.fail(&message),
+        }
+    }
+
+    /// Transform the single-line plain scalar at `start..end` if its path is
+    /// selected. If the replacements stop it being a valid plain scalar, it's
+    /// written double-quoted instead, and we return true.
+    fn plain(&mut self, start: usize, end: usize, flow: bool) -> bool {
+        let Some((decoded, replacements)) = self.replacements(start, end, Decoded::plain)
+        else {
+            return false;
+        };
+        let replaced = decoded.replaced(&replacements);
+        // Text we couldn't make sense of (eg a mismatched flow collection) may
+        // not have been valid to begin with, and is left that way.
+        if is_plain(&replaced, flow) || !is_plain(&decoded.text, flow) {
+            match decoded.splice(&self.input[start..end], &replacements) {
+                Ok(spliced) => self.write(start, end, &spliced),
+                Err(message) => self.fail(&message),
+            }
+            return false;
+        }
+        let escaped = Quoting::Backslashes.escape(&replaced).unwrap_or_default();
+        self.write(start, end, &format!("\"{}\"", escaped));
+        true
+    }
+
+    /// Decode the scalar at `start..end` and find its replacements, if its path
+    /// is selected and there are any.
+    fn replacements(
+        &mut self,
+        start: usize,
+        end: usize,
+        decode: impl FnOnce(&str) -> Decoded,
+    ) -> Option<(Decoded, Replacements)> {
+        let path = self.path();
+        if start >= end || !self.fields.selects(&path) {
+            return None;
+        }
+        let decoded = decode(&self.input[start..end]);
+        let replacements = (self.transform)(&decoded.text);
+        (!replacements.is_empty()).then_some((decoded, replacements))
+    }
+
+    /// Write the raw text of a transformed scalar in place of `start..end`.
+    fn write(&mut self, start: usize, end: usize, raw: &str) {
+        self.emit(start);
+        self.output.push_str(raw);
+        self.copied = end;
+    }
+
+    /// Record an error, if there isn't one already.
+    fn fail(&mut self, message: &str) {
+        if self.error.is_none() {
+            let line = self.input[..self.copied].matches('\n').count() + 1;
+            self.error = Some(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("can't write YAML at line {}: {}", line, message),
+            ));
+        }
+    }
+
+    /// Run something with extra elements temporarily appended to the path.
+    fn with_path(&mut self, extra: &[PathElem], f: impl FnOnce(&mut Self)) {
+        let depth = self.stack.len();
+        for elem in extra {
+            let indent = usize::MAX;
+            self.stack.push(match elem {
+                PathElem::Key(key) => Frame::Map {
+                    indent,
+                    key: key.clone(),
+                },
+                PathElem::Index(index) => Frame::Seq {
+                    indent,
+                    index: *index,
+                },
+            });
+        }
+        f(self);
+        self.stack.truncate(depth);
+    }
+
+    /// Scan a flow collection starting at `pos`, returning whether it made
+    /// sense (in which case its scalars have been scanned).
+    fn flow(&mut self, pos: usize, text: &str) -> bool {
+        let mut flow = Flow {
+            text,
+            pos: 0,
+            spans: Vec::new(),
+        };
+        if flow.node(&mut Vec::new()).is_none() {
+            return false;
+        }
+        for (path, start, stop, quote) in flow.spans {
+            self.with_path(&path, |scanner| match quote {
+                Some(quote) => scanner
+                    .scalar(pos + start, pos + stop, |raw| decode_quoted(raw, quote)),
+                None => {
+                    scanner.plain(pos + start, pos + stop, true);
+                }
+            });
+        }
+        true
+    }
+
+    /// The path of the current node.
+    fn path(&self) -> Vec<PathElem> {
+        self.stack
+            .iter()
+            .map(|frame| match frame {
+                Frame::Map { key, .. } => PathElem::Key(key.clone()),
+                Frame::Seq { index, .. } => PathElem::Index(*index),
+            })
+            .collect()
+    }
+
+    /// The start of the line containing `pos`.
+    fn line_start(&self, pos: usize) -> usize {
+        self.input[..pos].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    /// Pass through everything up to `pos`.
+    fn emit(&mut self, pos: usize) {
+        self.output.push_str(&self.input[self.copied..pos]);
+        self.copied = pos;
+    }
+}
+
+/// Simple single-line flow collections (`[a, b]`, `{k: v}`), collecting the
+/// location and path of each scalar within them.
+struct Flow<'a> {
+    text: &'a str,
+    pos: usize,
+    spans: Vec<(Vec<PathElem>, usize, usize, Option<char>)>,
+}
+
+impl Flow<'_> {
+    /// Parse a node, returning `None` if we can't make sense of it.
+    fn node(&mut self, path: &mut Vec<PathElem>) -> Option<()> {
+        self.whitespace();
+        let rest = &self.text[self.pos..];
+        match rest.chars().next()? {
+            open @ ('[' | '{') => {
+                let close = if open == '[' { ']' } else { '}' };
+                self.pos += 1;
+                for index in 0.. {
+                    self.whitespace();
+                    if self.text[self.pos..].starts_with(close) {
+                        break;
+                    }
+                    if open == '{' {
+                        let (start, stop, quote) = self.scalar()?;
+                        self.whitespace();
+                        self.text[self.pos..].starts_with(':').then_some(())?;
+                        self.pos += 1;
+                        let raw = &self.text[start..stop];
+                        let key = match quote {
+                            Some(quote) => decode_quoted(raw, quote).text,
+                            None => raw.to_string(),
+                        };
+                        path.push(PathElem::Key(key));
+                    } else {
+                        path.push(PathElem::Index(index));
+                    }
+                    self.node(path)?;
+                    path.pop();
+                    self.whitespace();
+                    if self.text[self.pos..].starts_with(',') {
+                        self.pos += 1;
+                    } else if !self.text[self.pos..].starts_with(close) {
+                        return None;
+                    }
+                }
+                self.pos += 1;
+            }
+            _ => {
+                let (start, stop, quote) = self.scalar()?;
+                self.spans.push((path.clone(), start, stop, quote));
+            }
+        }
+        Some(())
+    }
+
+    /// Parse a scalar, returning its (unquoted) range, and quote if any.
+    fn scalar(&mut self) -> Option<(usize, usize, Option<char>)> {
+        let rest = &self.text[self.pos..];
+        let start = self.pos;
+        match rest.chars().next()? {
+            quote @ ('"' | '\'') => {
+                let close = closing_quote(&rest[1..], quote)?;
+                self.pos += close + 2;
+                Some((start + 1, start + 1 + close, Some(quote)))
+            }
+            _ => {
+                // An empty plain scalar means something unexpected (eg the wrong
+                // closing bracket) is in the way.
+                self.pos += plain_end(rest, 0, true);
+                (self.pos > start).then_some((start, self.pos, None))
+            }
+        }
+    }
+
+    fn whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+}
+
+/// Find a mapping key at the start of `text`, returning the end of the key and
+/// the position of the `:` indicator.
+fn find_key(text: &str) -> Option<(usize, usize)> {
+    let key_end = match text.chars().next()? {
+        quote @ ('"' | '\'') => closing_quote(&text[1..], quote)? + 2,
+        '[' | '{' | '#' | '|' | '>' | '*' | '!' | '&' | '?' => return None,
+        _ => {
+            let colon = text.match_indices(':').map(|(i, _)| i).find(|&i| {
+                text[i + 1..].is_empty() || text[i + 1..].starts_with([' ', '\t'])
+            })?;
+            if text[..colon].contains(" #") {
+                return None;
+            }
+            return Some((text[..colon].trim_end().len(), colon));
+        }
+    };
+    let colon = key_end + (text[key_end..].len() - text[key_end..].trim_start().len());
+    let after = &text[colon..];
+    (after.starts_with(':') && (after.len() == 1 || after[1..].starts_with([' ', '\t'])))
+        .then_some((key_end, colon))
+}
+
+/// The end of a plain scalar starting at `start`: either a comment, or (in a
+/// flow collection) a flow indicator. Trailing whitespace is excluded.
+fn plain_end(text: &str, start: usize, flow: bool) -> usize {
+    let mut end = text.len();
+    let mut previous = ' ';
+    for (i, c) in text[start..].char_indices() {
+        if (c == '#' && previous.is_whitespace()) || (flow && ",]}".contains(c)) {
+            end = start + i;
+            break;
+        }
+        if flow && c == ':' && text[start + i + 1..].starts_with([' ', ',', ']', '}']) {
+            end = start + i;
+            break;
+        }
+        previous = c;
+    }
+    text[..end].trim_end().len()
+}
+
+/// Find the closing quote in the contents of a quoted scalar.
+fn closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            ('"', '\\') => {
+                chars.next();
+            }
+            ('\'', '\'') if chars.peek().is_some_and(|(_, c)| *c == '\'') => {
+                chars.next();
+            }
+            _ if c == quote => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether some text can be written as a plain scalar, erring on the side of
+/// saying no.
+fn is_plain(text: &str, flow: bool) -> bool {
+    !text.starts_with(|c: char| c.is_whitespace() || "-?:,[]{}#&*!|>'\"%@`".contains(c))
+        && !text.ends_with(|c: char| c.is_whitespace() || c == ':')
+        && !text.contains(": ")
+        && !text.contains(" #")
+        && !text
+            .chars()
+            .any(|c| c.is_control() || (flow && ",[]{}".contains(c)))
+}
+
+/// Decode a scalar that may or may not be quoted.
+fn decode_scalar(raw: &str) -> Decoded {
+    match raw.chars().next() {
+        Some(quote @ ('"' | '\'')) if raw.len() >= 2 && raw.ends_with(quote) => {
+            decode_quoted(&raw[1..raw.len() - 1], quote)
+        }
+        _ => Decoded::plain(raw),
+    }
+}
+
+/// Decode the contents of a quoted scalar.
+fn decode_quoted(raw: &str, quote: char) -> Decoded {
+    if quote == '"' {
+        return Decoded::backslashes(raw);
+    }
+    let mut decoded = Decoded::default().with_quoting(Quoting::Doubled);
+    let mut chars = raw.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        if c == '\'' {
+            chars.next();
+        }
+        decoded.push(c, offset);
+    }
+    decoded.finish(raw.len());
+    decoded
+}
+
+/// Tests
+#[cfg(test)]
+mod tests {
+    use super::transform;
+    use crate::structured::{Fields, mark, replace_all};
+
+    #[test]
+    fn values_only() {
+        let input = "\
+# xxx comment
+xxx: xxx # xxx
+a:
+  - xxx
+  - b: 'xxx''s'
+    c: \"xxx\\x78\"
+  - &anchor !!str xxx
+d: [xxx, {xxx: xxx}]
+";
+        let output = "\
+# xxx comment
+xxx: <xxx> # xxx
+a:
+  - <xxx>
+  - b: '<xxx>''s'
+    c: \"<xxxx>\"
+  - &anchor !!str <xxx>
+d: [<xxx>, {xxx: <xxx>}]
+";
+        assert_eq!(mark(transform, input, &Fields::default()), output);
+    }
+
+    #[test]
+    fn escaping() {
+        let input = "a: \"xxx\"\nb: 'xxx'\nc: xxx\nd: [xxx, 'xxx']\n";
+        assert_eq!(
+            replace_all(transform, input, r#"a"b\c'"#).unwrap(),
+            r#"a: "a\"b\\c'"
+b: 'a"b\c'''
+c: a"b\c'
+d: [a"b\c', 'a"b\c''']
+"#
+        );
+        // Plain scalars are quoted where the replacement can't be written plain
+        assert_eq!(
+            replace_all(transform, "xxx: xxx\nd: [xxx, b]\n", "*,").unwrap(),
+            "\"*,\": \"*,\"\nd: [\"*,\", b]\n"
+        );
+        for bad in ["c: xxx\n  continued\n", "b: 'xxx'\n", "e: |\n  xxx\n"] {
+            assert!(replace_all(transform, bad, "*\n").is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn mismatched_brackets() {
+        // Flow collections that don't make sense are treated as plain text.
+        let input = "tags: [xxx, xxx}\n[}\nmore: {xxx: xxx]\n";
+        let output = "tags: [<xxx>, <xxx>}\n[}\nmore: {<xxx>: <xxx>]\n";
+        assert_eq!(mark(transform, input, &Fields::default()), output);
+    }
+
+    #[test]
+    fn multi_line_scalars() {
+        let input = "\
+cert: |
+  xxx
+    xxx
+key: >-
+  xxx
+
+name: plain xxx
+  continued xxx
+next: \"quoted xxx
+  continued xxx\"
+";
+        let output = "\
+cert: |
+  <xxx>
+    <xxx>
+key: >-
+  <xxx>
+
+name: plain <xxx>
+  continued <xxx>
+next: \"quoted <xxx>
+  continued <xxx>\"
+";
+        assert_eq!(mark(transform, input, &Fields::default()), output);
+    }
+
+    #[test]
+    fn fields_and_keys() {
+        let input = "\
+metadata:
+  name: xxx
+data:
+  xxx: xxx
+list:
+- xxx
+- xxx
+---
+metadata:
+  name: xxx
+";
+        let fields = Fields {
+            include: vec!["$.data".parse().unwrap(), "$.list[1]".parse().unwrap()],
+            keys: true,
+            ..Fields::default()
+        };
+        let output = "\
+metadata:
+  name: xxx
+data:
+  <xxx>: <xxx>
+list:
+- xxx
+- <xxx>
+---
+metadata:
+  name: xxx
+";
+        assert_eq!(mark(transform, input, &fields), output);
+        let fields = Fields {
+            exclude: vec!["metadata.name".parse().unwrap()],
+            ..Fields::default()
+        };
+        assert_eq!(
+            mark(transform, "metadata:\n  name: xxx\n  id: xxx\n", &fields),
+            "metadata:\n  name: xxx\n  id: <xxx>\n"
+        );
+    }
+}